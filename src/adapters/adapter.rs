@@ -1,6 +1,7 @@
 use egui::Color32;
 
 use crate::app::*;
+use crate::compatibility::ToolInterface;
 use crate::hydraulic::*;
 use crate::resources::*;
 
@@ -58,6 +59,18 @@ impl Adapter {
             Adapter::Hydraulic(hydraulic) => hydraulic.get_type(),
         }
     }
+
+    pub fn get_spindle_interface(&self) -> Option<ToolInterface> {
+        match self {
+            Adapter::Hydraulic(hydraulic) => hydraulic.get_spindle_interface(),
+        }
+    }
+
+    pub fn get_holder_interface(&self) -> Option<ToolInterface> {
+        match self {
+            Adapter::Hydraulic(hydraulic) => hydraulic.get_holder_interface(),
+        }
+    }
 }
 
 pub fn add_adapter(app: &mut ManagingApp, ctx: &egui::Context) {
@@ -1,11 +1,16 @@
 use egui::Color32;
 
 use crate::adapter::AdapterCategory;
+use crate::compatibility::*;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Hydraulic {
     pub name: String,
     pub color: Color32,
+    #[serde(default)]
+    pub spindle_interface: Option<ToolInterface>, // Side mounted in the spindle
+    #[serde(default)]
+    pub holder_interface: Option<ToolInterface>, // Side receiving the holder
 }
 
 impl Default for Hydraulic {
@@ -13,6 +18,8 @@ impl Default for Hydraulic {
         Self {
             name: "Hydraulic".to_string(),
             color: Color32::GREEN,
+            spindle_interface: None,
+            holder_interface: None,
         }
     }
 }
@@ -24,6 +31,8 @@ impl Hydraulic {
             ui.separator();
             ui.text_edit_singleline(&mut self.name);
         });
+        interface_combo(ui, "Spindle side", &mut self.spindle_interface);
+        interface_combo(ui, "Holder side", &mut self.holder_interface);
         ui.separator();
         *add = ui.button("Add").clicked()
     }
@@ -52,6 +61,14 @@ impl Hydraulic {
         AdapterCategory::Standard
     }
 
+    pub fn get_spindle_interface(&self) -> Option<ToolInterface> {
+        self.spindle_interface
+    }
+
+    pub fn get_holder_interface(&self) -> Option<ToolInterface> {
+        self.holder_interface
+    }
+
     pub fn get_type(&self) -> String {
         "Hydraulic".to_string()
     }
@@ -1,17 +1,26 @@
 use crate::adapter::*;
+use crate::assembly::*;
 use crate::calculations::calculations::*;
+use crate::compatibility::*;
+use crate::geometry::*;
+use crate::history::*;
 
 use crate::holder::*;
 use crate::library::*;
 use crate::machine::*;
 use crate::magazine::*;
+use crate::material::*;
+use crate::offset_transfer::*;
+use crate::program_check::*;
 use egui::Visuals;
 use strum::IntoEnumIterator;
 
 use crate::tool::*;
+use crate::transfer::*;
+use crate::turret::*;
 
 use crate::resources::*;
-use crate::Comment;
+use crate::slot::*;
 use egui::widgets::color_picker::*;
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -21,10 +30,13 @@ pub struct ManagingApp {
     pub selections: Selections,
     pub app_states: ActiveState,
     pub library: Library,
+    pub materials: MaterialCatalogue,
     pub gui_singletons: GuiSingletons,
     pub display_magazine: Magazine,
     #[serde(skip)]
     pub move_selections: MagazineLibraryMovingSelections,
+    #[serde(skip)]
+    pub history: History,
 }
 
 impl Default for ManagingApp {
@@ -36,11 +48,14 @@ impl Default for ManagingApp {
             app_states: ActiveState::default(),
             gui_singletons: GuiSingletons::default(),
             library: Library::default(),
+            materials: MaterialCatalogue::default(),
             display_magazine: Magazine {
                 name: "None selected".to_string(),
                 contents: Vec::new(),
+                kind: MagazineKind::default(),
             },
             move_selections: MagazineLibraryMovingSelections::default(),
+            history: History::default(),
         }
     }
 }
@@ -58,7 +73,10 @@ impl ManagingApp {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app: ManagingApp =
+                eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            app.gui_singletons.add_missing_templates();
+            return app;
         }
 
         Default::default()
@@ -86,9 +104,12 @@ impl eframe::App for ManagingApp {
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         //log_states(self);
+        history_shortcuts(self, ctx);
         self.left_panel(ctx);
         self.central_panel(ctx);
+        let operation = operation_label(self);
         self.handle_state_transitions(ctx);
+        record_history(self, ctx, operation);
     }
 }
 
@@ -108,6 +129,13 @@ impl ManagingApp {
             if ui.button("Settings").clicked() {
                 self.app_states.app_state = AppState::Settings;
             }
+            if ui
+                .button("History")
+                .on_hover_text("Undo and redo changes to machines and library")
+                .clicked()
+            {
+                self.app_states.app_state = AppState::History;
+            }
         });
         ui.separator();
         if ui
@@ -152,13 +180,59 @@ impl ManagingApp {
             print!("Add adapter button clicked!");
             self.app_states.app_state = AppState::AddAdapter;
         }
+        if ui
+            .add(egui::Button::new("Build assembly"))
+            .on_hover_text("Combine a tool, holder and adapter from the library")
+            .clicked()
+        {
+            self.app_states.app_state = AppState::BuildAssembly;
+        }
         ui.separator();
         select_machine(self, ui);
         select_magazine(self, ui);
+        if self.selections.machine.is_some()
+            && ui
+                .add(egui::Button::new("Edit machine"))
+                .on_hover_text("Rename, resize or delete the selected machine")
+                .clicked()
+        {
+            self.app_states.app_state = AppState::EditMachine;
+        }
+        if self.selections.machine.is_some()
+            && ui
+                .add(egui::Button::new("Capabilities"))
+                .on_hover_text("Spindle interface and limits of the selected machine")
+                .clicked()
+        {
+            self.app_states.app_state = AppState::MachineCapabilities;
+        }
+        if self.selections.machine.is_some()
+            && ui
+                .add(egui::Button::new("Offset transfer"))
+                .on_hover_text("Export or import tool offsets for the selected machine")
+                .clicked()
+        {
+            self.app_states.app_state = AppState::OffsetTransfer;
+        }
+        if self.selections.machine.is_some()
+            && ui
+                .add(egui::Button::new("Program check"))
+                .on_hover_text("Check the tools a part program calls against the selected machine")
+                .clicked()
+        {
+            self.app_states.app_state = AppState::ProgramCheck;
+        }
         ui.separator();
         if ui.button("Utility calculations").clicked() {
             self.app_states.app_state = AppState::Calculations;
         }
+        if ui
+            .button("Materials")
+            .on_hover_text("Workpiece materials used by the calculations")
+            .clicked()
+        {
+            self.app_states.app_state = AppState::Materials;
+        }
     }
 
     pub fn central_panel(&mut self, ctx: &egui::Context) {
@@ -185,7 +259,12 @@ impl ManagingApp {
             AppState::AddAdapter => add_adapter(self, ctx),
             AppState::AddMachine => add_machine(self, ctx),
             AppState::ShowLibrary => {
-                if !self.library.display(ctx) {
+                let mut dropped_slot = None;
+                let is_open = self.library.display(ctx, &mut dropped_slot);
+                if let Some(pocket) = dropped_slot {
+                    start_drop(self, DragItem::Slot(pocket), None);
+                }
+                if !is_open {
                     reset_states(self);
                 }
             }
@@ -193,6 +272,13 @@ impl ManagingApp {
             AppState::Calculations => {
                 calculations(self, ctx);
             }
+            AppState::OffsetTransfer => offset_transfer(self, ctx),
+            AppState::ProgramCheck => program_check(self, ctx),
+            AppState::Materials => material_catalogue(self, ctx),
+            AppState::BuildAssembly => build_assembly(self, ctx),
+            AppState::MachineCapabilities => machine_capabilities(self, ctx),
+            AppState::EditMachine => edit_machine(self, ctx),
+            AppState::History => history_panel(self, ctx),
         }
     }
 
@@ -202,8 +288,12 @@ impl ManagingApp {
                 MoveStates::ToolToMagazine => {
                     self.gui_singletons.tool_filter = None;
                     self.gui_singletons.sort_by = SortBy::Slot;
-                    select_tool_from_library(self, ctx);
+                    if self.move_selections.selected_tool_index_library.is_none() {
+                        select_tool_from_library(self, ctx);
+                    }
                     move_tool_to_magazine(self);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
                 }
                 MoveStates::ToolToLibrary => {
                     self.gui_singletons.tool_filter = None;
@@ -211,15 +301,27 @@ impl ManagingApp {
                     move_tool_to_library(self);
                 }
                 MoveStates::HolderToMagazine => {
-                    select_holder_from_library(self, ctx);
+                    if self.move_selections.selected_holder_index_library.is_none() {
+                        select_holder_from_library(self, ctx);
+                    }
                     move_holder_to_magazine(self);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
                 }
                 MoveStates::HolderToLibrary => {
                     move_holder_to_library(self);
                 }
                 MoveStates::AdapterToMagazine => {
-                    select_adapter_from_library(self, ctx);
+                    if self
+                        .move_selections
+                        .selected_adapter_index_library
+                        .is_none()
+                    {
+                        select_adapter_from_library(self, ctx);
+                    }
                     move_adapter_to_magazine(self);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
                 }
                 MoveStates::AdapterToLibrary => {
                     move_adapter_to_library(self);
@@ -227,6 +329,34 @@ impl ManagingApp {
                 MoveStates::EditComment => {
                     edit_comment(self, ctx);
                 }
+                MoveStates::EditSlot => {
+                    edit_slot(self, ctx);
+                }
+                MoveStates::AssemblyToMagazine => {
+                    if self
+                        .move_selections
+                        .selected_assembly_index_library
+                        .is_none()
+                    {
+                        select_assembly_from_library(self, ctx);
+                    }
+                    move_assembly_to_magazine(self);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
+                }
+                MoveStates::AssemblyToLibrary => {
+                    move_assembly_to_library(self);
+                }
+                MoveStates::SwapSlots => {
+                    swap_slots(self);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
+                }
+                MoveStates::TransferSlot => {
+                    transfer_slot_window(self, ctx);
+                    move_refusal(self, ctx);
+                    compatibility_warning(self, ctx);
+                }
             },
             None => {}
         }
@@ -234,7 +364,10 @@ impl ManagingApp {
 }
 
 pub fn reset_states(app: &mut ManagingApp) {
-    app.app_states.app_state = AppState::ShowMagazine;
+    // Moves started by drag and drop leave the window the item was dragged from open
+    if !std::mem::take(&mut app.move_selections.from_drop) {
+        app.app_states.app_state = AppState::ShowMagazine;
+    }
     app.app_states.add_tool_state = None;
     app.app_states.add_holder_state = None;
     app.app_states.add_adapter_state = None;
@@ -249,6 +382,14 @@ pub fn reset_states(app: &mut ManagingApp) {
     app.move_selections.selected_holder_index_library = None;
     app.move_selections.selected_adapter_index_library = None;
     app.move_selections.selected_comment_index_magazine = None;
+    app.move_selections.selected_slot_index_magazine = None;
+    app.move_selections.selected_assembly_index_magazine = None;
+    app.move_selections.selected_assembly_index_library = None;
+    app.move_selections.mount_violations.clear();
+    app.move_selections.mount_override = false;
+    app.move_selections.move_refusal = None;
+    app.move_selections.swap_target_index_magazine = None;
+    app.move_selections.transfer_requested = false;
 }
 
 pub fn filter_by_tool_category(app: &mut ManagingApp, ui: &mut egui::Ui) {
@@ -276,6 +417,7 @@ pub fn filter_by_tool_category(app: &mut ManagingApp, ui: &mut egui::Ui) {
                     .clicked()
                 {
                     app.gui_singletons.tool_filter = None;
+                    app.gui_singletons.material_filter = None;
                     app.display_magazine.contents = magazine.contents.clone(); //get_sorted_by_tool_diameter(&mut magazine.contents);
                 }
                 if ui
@@ -286,10 +428,9 @@ pub fn filter_by_tool_category(app: &mut ManagingApp, ui: &mut egui::Ui) {
                     .clicked()
                 {
                     app.gui_singletons.tool_filter = Some(ToolState::Rotating);
-                    app.display_magazine.contents = get_filtered_by_tool_category(
-                        &mut magazine.contents,
-                        ToolCategory::Rotating,
-                    );
+                    app.gui_singletons.material_filter = None;
+                    app.display_magazine.contents =
+                        get_filtered_by_tool_category(&magazine.contents, ToolCategory::Rotating);
                 }
                 if ui
                     .selectable_label(
@@ -299,19 +440,50 @@ pub fn filter_by_tool_category(app: &mut ManagingApp, ui: &mut egui::Ui) {
                     .clicked()
                 {
                     app.gui_singletons.tool_filter = Some(ToolState::Insert);
+                    app.gui_singletons.material_filter = None;
                     app.display_magazine.contents = get_filtered_by_tool_category(
-                        &mut magazine.contents,
+                        &magazine.contents,
                         ToolCategory::LatheInsert,
                     );
                 }
             });
+        let material_name = app
+            .gui_singletons
+            .material_filter
+            .map(|material| material.to_string())
+            .unwrap_or("All".to_string());
+        egui::ComboBox::from_label("Tool material")
+            .selected_text(material_name)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(app.gui_singletons.material_filter.is_none(), "All")
+                    .clicked()
+                {
+                    app.gui_singletons.material_filter = None;
+                    app.gui_singletons.tool_filter = None;
+                    app.display_magazine.contents = magazine.contents.clone();
+                }
+                for material in ToolMaterial::iter() {
+                    if ui
+                        .selectable_label(
+                            app.gui_singletons.material_filter == Some(material),
+                            material.to_string(),
+                        )
+                        .clicked()
+                    {
+                        app.gui_singletons.material_filter = Some(material);
+                        app.gui_singletons.tool_filter = Some(ToolState::Rotating);
+                        app.display_magazine.contents =
+                            get_filtered_by_tool_material(&magazine.contents, material);
+                    }
+                }
+            });
     });
 }
 
 pub fn sort_by(app: &mut ManagingApp, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
-        let name;
-        name = app.gui_singletons.sort_by.to_string();
+        let name = app.gui_singletons.sort_by.to_string();
         if app.machines.is_empty() || app.selections.machine.is_none() {
             return;
         }
@@ -334,7 +506,7 @@ pub fn sort_by(app: &mut ManagingApp, ui: &mut egui::Ui) {
                     app.gui_singletons.sort_by = SortBy::Slot;
 
                     app.display_magazine.contents = get_sorted_by_slot(
-                        &mut magazine.contents,
+                        &magazine.contents,
                         app.gui_singletons.tool_filter.clone(),
                     );
                 }
@@ -347,8 +519,7 @@ pub fn sort_by(app: &mut ManagingApp, ui: &mut egui::Ui) {
                 {
                     app.gui_singletons.tool_filter = Some(ToolState::Rotating);
                     app.gui_singletons.sort_by = SortBy::Diameter;
-                    app.display_magazine.contents =
-                        get_sorted_by_tool_diameter(&mut magazine.contents);
+                    app.display_magazine.contents = get_sorted_by_tool_diameter(&magazine.contents);
                 }
                 if ui
                     .selectable_label(
@@ -359,7 +530,46 @@ pub fn sort_by(app: &mut ManagingApp, ui: &mut egui::Ui) {
                 {
                     app.gui_singletons.sort_by = SortBy::Degree;
                     app.gui_singletons.tool_filter = Some(ToolState::Insert);
-                    app.display_magazine.contents = get_sorted_by_degree(&mut magazine.contents);
+                    app.display_magazine.contents = get_sorted_by_degree(&magazine.contents);
+                }
+                if ui
+                    .selectable_label(
+                        app.gui_singletons.sort_by == SortBy::ToolType,
+                        SortBy::ToolType.to_string(),
+                    )
+                    .clicked()
+                {
+                    app.gui_singletons.sort_by = SortBy::ToolType;
+                    app.gui_singletons.tool_filter = Some(ToolState::Rotating);
+                    app.display_magazine.contents = get_sorted_by_tool_type(&magazine.contents);
+                }
+                if ui
+                    .selectable_label(
+                        app.gui_singletons.sort_by == SortBy::Flutes,
+                        SortBy::Flutes.to_string(),
+                    )
+                    .clicked()
+                {
+                    app.gui_singletons.sort_by = SortBy::Flutes;
+                    app.gui_singletons.tool_filter = Some(ToolState::Rotating);
+                    app.display_magazine.contents =
+                        get_sorted_by_geometry(&magazine.contents, |geometry| {
+                            geometry.flutes as f32
+                        });
+                }
+                if ui
+                    .selectable_label(
+                        app.gui_singletons.sort_by == SortBy::OverallLength,
+                        SortBy::OverallLength.to_string(),
+                    )
+                    .clicked()
+                {
+                    app.gui_singletons.sort_by = SortBy::OverallLength;
+                    app.gui_singletons.tool_filter = Some(ToolState::Rotating);
+                    app.display_magazine.contents =
+                        get_sorted_by_geometry(&magazine.contents, |geometry| {
+                            geometry.overall_length
+                        });
                 }
             });
     });
@@ -392,12 +602,12 @@ pub fn settings(app: &mut ManagingApp, ctx: &egui::Context) {
                     for state in ColorSettingsState::iter() {
                         if ui
                             .selectable_label(
-                                &mut app.gui_singletons.color_settings_state == &state,
+                                app.gui_singletons.color_settings_state == state,
                                 state.to_string(),
                             )
                             .clicked()
                         {
-                            *&mut app.gui_singletons.color_settings_state = state;
+                            app.gui_singletons.color_settings_state = state;
                             app.gui_singletons.color_settings.index = Some(0);
                         }
                     }
@@ -539,12 +749,44 @@ pub fn settings(app: &mut ManagingApp, ctx: &egui::Context) {
                     }
                 }
             }
+            ui.separator();
+            wear_limit_settings(&mut app.gui_singletons.wear_limits, ui);
         });
     if !is_window_open {
         reset_states(app);
     }
 }
 
+pub fn wear_limit_settings(wear_limits: &mut WearLimits, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Wear limits").heading())
+        .on_hover_text("Largest allowed length/radius wear before a slot is flagged");
+    egui::Grid::new("wear_limits_grid")
+        .num_columns(3)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.label("Length");
+            ui.label("Radius");
+            ui.end_row();
+            for (name, limit) in [
+                ("Rotating", &mut wear_limits.rotating),
+                ("Insert", &mut wear_limits.lathe_insert),
+            ] {
+                ui.label(name);
+                ui.add(
+                    egui::DragValue::new(&mut limit.length)
+                        .speed(0.001)
+                        .clamp_range(0.0..=10.0),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut limit.radius)
+                        .speed(0.001)
+                        .clamp_range(0.0..=10.0),
+                );
+                ui.end_row();
+            }
+        });
+}
+
 pub fn apply_colors(app: &mut ManagingApp) {
     // apply_colors_to_contents(&mut app.display_magazine.contents, &app.gui_singletons);
     for machine in app.machines.iter_mut() {
@@ -554,18 +796,9 @@ pub fn apply_colors(app: &mut ManagingApp) {
     }
 }
 
-pub fn apply_colors_to_contents(
-    contents: &mut Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
-    gui_singletons: &GuiSingletons,
-) {
-    for content in contents.iter_mut() {
-        if let Some(tool) = &mut content.1 {
+pub fn apply_colors_to_contents(contents: &mut [Slot], gui_singletons: &GuiSingletons) {
+    for slot in contents.iter_mut() {
+        if let Some(tool) = &mut slot.tool {
             for rotating_tool in gui_singletons.rotating_tools.iter() {
                 if rotating_tool.get_type() == tool.get_type() {
                     tool.set_color(rotating_tool.get_color());
@@ -576,14 +809,14 @@ pub fn apply_colors_to_contents(
                     tool.set_color(insert_tool.get_color());
                 }
             }
-            if let Some(holder) = &mut content.2 {
+            if let Some(holder) = &mut slot.holder {
                 for holder_singleton in gui_singletons.holders.iter() {
                     if holder_singleton.get_type() == holder.get_type() {
                         holder.set_color(holder_singleton.get_color());
                     }
                 }
             }
-            if let Some(adapter) = &mut content.3 {
+            if let Some(adapter) = &mut slot.adapter {
                 for adapter_singleton in gui_singletons.adapters.iter() {
                     if adapter_singleton.get_type() == adapter.get_type() {
                         adapter.set_color(adapter_singleton.get_color());
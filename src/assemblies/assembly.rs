@@ -0,0 +1,321 @@
+use crate::adapter::*;
+use crate::holder::*;
+use crate::slot::*;
+use crate::tool::*;
+use crate::{reset_states, ManagingApp};
+
+/// Lengths of an assembly along the spindle axis [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct AssemblyDimensions {
+    pub tool_length: f32,    // Overall length of the tool
+    pub clamping_depth: f32, // Length of the tool shank inside the holder
+    pub holder_length: f32,  // Gauge line to holder nose
+    pub adapter_length: f32, // Length the adapter adds between spindle and holder
+    #[serde(default)]
+    pub weight: f32, // [kg], 0 when not known
+}
+
+impl Default for AssemblyDimensions {
+    fn default() -> Self {
+        Self {
+            tool_length: 75.0,
+            clamping_depth: 35.0,
+            holder_length: 60.0,
+            adapter_length: 0.0,
+            weight: 0.0,
+        }
+    }
+}
+
+impl AssemblyDimensions {
+    /// Length of the tool protruding from the holder nose.
+    pub fn stick_out(&self) -> f32 {
+        (self.tool_length - self.clamping_depth).max(0.0)
+    }
+
+    /// Gauge line to tool tip.
+    pub fn gauge_length(&self, has_adapter: bool) -> f32 {
+        let adapter_length = if has_adapter {
+            self.adapter_length
+        } else {
+            0.0
+        };
+        adapter_length + self.holder_length + self.stick_out()
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui, has_adapter: bool) {
+        egui::Grid::new("assembly_dimensions_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Tool length:");
+                ui.add(egui::DragValue::new(&mut self.tool_length).clamp_range(0.0..=1000.0));
+                ui.end_row();
+                ui.label("Clamping depth:")
+                    .on_hover_text("Length of the tool shank inside the holder");
+                ui.add(
+                    egui::DragValue::new(&mut self.clamping_depth)
+                        .clamp_range(0.0..=self.tool_length),
+                );
+                ui.end_row();
+                ui.label("Holder length:")
+                    .on_hover_text("Gauge line to holder nose");
+                ui.add(egui::DragValue::new(&mut self.holder_length).clamp_range(0.0..=1000.0));
+                ui.end_row();
+                if has_adapter {
+                    ui.label("Adapter length:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.adapter_length).clamp_range(0.0..=1000.0),
+                    );
+                    ui.end_row();
+                }
+                ui.label("Weight [kg]:")
+                    .on_hover_text("Leave at 0 when not known");
+                ui.add(
+                    egui::DragValue::new(&mut self.weight)
+                        .speed(0.1)
+                        .clamp_range(0.0..=100.0),
+                );
+                ui.end_row();
+                ui.label("Stick-out:");
+                ui.label(format!("{:.3}", self.stick_out()));
+                ui.end_row();
+                ui.label("Gauge length:");
+                ui.strong(format!("{:.3}", self.gauge_length(has_adapter)));
+                ui.end_row();
+            });
+    }
+}
+
+/// Marks the contents of a slot as a loaded assembly, so it can be unloaded as one unit.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct AssemblyTag {
+    pub id: u32,
+    pub name: String,
+    pub dimensions: AssemblyDimensions,
+}
+
+/// A tool mounted in a holder, optionally through an adapter, kept together as one unit.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ToolAssembly {
+    pub id: u32,
+    pub name: String,
+    pub tool: Tool,
+    pub holder: Holder,
+    pub adapter: Option<Adapter>,
+    pub dimensions: AssemblyDimensions,
+}
+
+impl ToolAssembly {
+    pub fn stick_out(&self) -> f32 {
+        self.dimensions.stick_out()
+    }
+
+    pub fn gauge_length(&self) -> f32 {
+        self.dimensions.gauge_length(self.adapter.is_some())
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong(format!("A{} {}", self.id, self.name));
+            ui.separator();
+            self.tool.display(ui);
+            ui.separator();
+            self.holder.display(ui);
+            if let Some(adapter) = &self.adapter {
+                ui.separator();
+                adapter.display(ui);
+            }
+            ui.separator();
+            ui.label(format!(
+                "Gauge {:.2} | Stick-out {:.2}",
+                self.gauge_length(),
+                self.stick_out()
+            ));
+        });
+    }
+
+    /// Mounts the assembly in a slot whose previous contents have already been removed. The
+    /// gauge length becomes the length geometry offset.
+    pub fn load_into(self, slot: &mut Slot) {
+        slot.offsets.length_geometry = self.gauge_length();
+        slot.tool = Some(self.tool);
+        slot.holder = Some(self.holder);
+        slot.adapter = self.adapter;
+        slot.assembly = Some(AssemblyTag {
+            id: self.id,
+            name: self.name,
+            dimensions: self.dimensions,
+        });
+        slot.refresh_state();
+    }
+
+    /// Takes an assembly back out of a slot, including changes made to its parts while
+    /// loaded. Returns `None` and leaves the slot untouched if it holds no assembly.
+    pub fn unload_from(slot: &mut Slot) -> Option<ToolAssembly> {
+        let (Some(_), Some(_), Some(_)) = (&slot.assembly, &slot.tool, &slot.holder) else {
+            return None;
+        };
+        let tag = slot.assembly.take()?;
+        let assembly = ToolAssembly {
+            id: tag.id,
+            name: tag.name,
+            tool: slot.tool.take()?,
+            holder: slot.holder.take()?,
+            adapter: slot.adapter.take(),
+            dimensions: tag.dimensions,
+        };
+        slot.refresh_state();
+        Some(assembly)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct AssemblyBuilderFields {
+    pub name: String,
+    pub tool_index: Option<usize>,
+    pub holder_index: Option<usize>,
+    pub adapter_index: Option<usize>,
+    pub dimensions: AssemblyDimensions,
+}
+
+/// One above the highest assembly ID in the library or any magazine.
+pub fn next_assembly_id(app: &ManagingApp) -> u32 {
+    let in_library = app.library.assemblies.iter().map(|assembly| assembly.id);
+    let in_magazines = app
+        .machines
+        .iter()
+        .flat_map(|machine| machine.magazines.iter())
+        .flat_map(|magazine| magazine.contents.iter())
+        .filter_map(|slot| slot.assembly.as_ref().map(|tag| tag.id));
+    in_library.chain(in_magazines).max().map_or(1, |id| id + 1)
+}
+
+fn library_combo<T>(
+    ui: &mut egui::Ui,
+    label: &str,
+    items: &[T],
+    name: impl Fn(&T) -> String,
+    selected: &mut Option<usize>,
+    optional: bool,
+) {
+    let selected_text = selected
+        .and_then(|index| items.get(index))
+        .map(&name)
+        .unwrap_or("None".to_string());
+    egui::ComboBox::from_label(label)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            if optional {
+                ui.selectable_value(selected, None, "None");
+            }
+            for (i, item) in items.iter().enumerate() {
+                ui.selectable_value(selected, Some(i), name(item));
+            }
+        });
+}
+
+pub fn build_assembly(app: &mut ManagingApp, ctx: &egui::Context) {
+    let mut is_window_open = true;
+    let mut should_build = false;
+    egui::Window::new("Build assembly")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let fields = &mut app.gui_singletons.assembly_builder;
+            let (tool_index, holder_index) = (fields.tool_index, fields.holder_index);
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut fields.name);
+            });
+            library_combo(
+                ui,
+                "Tool",
+                &app.library.tools,
+                |tool| tool.get_name(),
+                &mut fields.tool_index,
+                false,
+            );
+            library_combo(
+                ui,
+                "Holder",
+                &app.library.holders,
+                |holder| holder.get_name(),
+                &mut fields.holder_index,
+                false,
+            );
+            library_combo(
+                ui,
+                "Adapter",
+                &app.library.adapters,
+                |adapter| adapter.get_name(),
+                &mut fields.adapter_index,
+                true,
+            );
+            // Picking a tool or holder fills in the lengths they are known to have
+            if fields.tool_index != tool_index {
+                let tool_length = fields
+                    .tool_index
+                    .and_then(|index| app.library.tools.get(index))
+                    .and_then(|tool| tool.get_geometry())
+                    .map(|geometry| geometry.overall_length);
+                if let Some(tool_length) = tool_length {
+                    let dimensions = &mut fields.dimensions;
+                    dimensions.tool_length = tool_length;
+                    dimensions.clamping_depth = dimensions.clamping_depth.min(tool_length);
+                }
+            }
+            if fields.holder_index != holder_index {
+                let projection = fields
+                    .holder_index
+                    .and_then(|index| app.library.holders.get(index))
+                    .and_then(|holder| holder.get_projection_length());
+                if let Some(projection) = projection {
+                    fields.dimensions.holder_length = projection;
+                }
+            }
+            ui.separator();
+            fields.dimensions.edit(ui, fields.adapter_index.is_some());
+            ui.separator();
+            let complete = fields.tool_index.is_some() && fields.holder_index.is_some();
+            should_build = ui
+                .add_enabled(complete, egui::Button::new("Build"))
+                .on_disabled_hover_text("An assembly needs a tool and a holder")
+                .clicked();
+        });
+
+    if should_build {
+        let id = next_assembly_id(app);
+        let fields = std::mem::take(&mut app.gui_singletons.assembly_builder);
+        let library = &mut app.library;
+        let (Some(tool_index), Some(holder_index)) = (fields.tool_index, fields.holder_index)
+        else {
+            return;
+        };
+        if tool_index >= library.tools.len()
+            || holder_index >= library.holders.len()
+            || fields
+                .adapter_index
+                .is_some_and(|index| index >= library.adapters.len())
+        {
+            return;
+        }
+        let name = if fields.name.trim().is_empty() {
+            library.tools[tool_index].get_name()
+        } else {
+            fields.name
+        };
+        library.assemblies.push(ToolAssembly {
+            id,
+            name,
+            tool: library.tools.remove(tool_index),
+            holder: library.holders.remove(holder_index),
+            adapter: fields
+                .adapter_index
+                .map(|index| library.adapters.remove(index)),
+            dimensions: fields.dimensions,
+        });
+        reset_states(app);
+    }
+    if !is_window_open {
+        reset_states(app);
+    }
+}
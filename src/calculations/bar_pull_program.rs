@@ -0,0 +1,318 @@
+use std::fmt::{self, Write};
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::machine::*;
+use crate::ManagingApp;
+
+use super::calculations::{CuttingToolFields, MaterialFields, WorkpieceFields, MIN_COLUMN_WIDTH};
+use super::three_claw_pulling::ThreeClawPullingFields;
+
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum NcDialect {
+    #[default]
+    Fanuc,
+    Siemens,
+}
+
+impl fmt::Display for NcDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NcDialect::Fanuc => write!(f, "Fanuc"),
+            NcDialect::Siemens => write!(f, "Siemens"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct BarPullProgramFields {
+    pub dialect: NcDialect,
+    pub program_number: u32, // O-number for Fanuc, BAR_PULL_<n> for Siemens
+    pub claw_tool: u32,
+    pub facing_tool: u32,
+    pub parting_tool: u32,
+    pub pull_feed: f32,     // [mm/min]
+    pub spindle_speed: f32, // [rpm] while facing and parting
+    pub facing_feed: f32,   // [mm/rev]
+    pub parting_feed: f32,  // [mm/rev]
+    pub clearance: f32,     // Distance kept from the material on rapid moves
+    pub dwell: f32,         // [s] after opening and closing the chuck
+    #[serde(skip)]
+    pub text: String,
+}
+
+impl Default for BarPullProgramFields {
+    fn default() -> Self {
+        Self {
+            dialect: NcDialect::Fanuc,
+            program_number: 9100,
+            claw_tool: 1,
+            facing_tool: 2,
+            parting_tool: 3,
+            pull_feed: 1000.0,
+            spindle_speed: 1500.0,
+            facing_feed: 0.15,
+            parting_feed: 0.05,
+            clearance: 2.0,
+            dwell: 1.0,
+            text: String::new(),
+        }
+    }
+}
+
+/// Dialect specific spelling of the few words that differ.
+struct Dialect(NcDialect);
+
+impl Dialect {
+    fn comment(&self, text: &str) -> String {
+        match self.0 {
+            NcDialect::Fanuc => format!("({})", text.replace(['(', ')'], "").to_uppercase()),
+            NcDialect::Siemens => format!("; {}", text),
+        }
+    }
+
+    fn tool(&self, tool: u32) -> String {
+        match self.0 {
+            NcDialect::Fanuc => format!("T{:02}{:02}", tool, tool),
+            NcDialect::Siemens => format!("T{} D1", tool),
+        }
+    }
+
+    fn dwell(&self, seconds: f32) -> String {
+        match self.0 {
+            NcDialect::Fanuc => format!("G4 X{:.1}", seconds),
+            NcDialect::Siemens => format!("G4 F{:.1}", seconds),
+        }
+    }
+
+    fn metric(&self) -> &'static str {
+        match self.0 {
+            NcDialect::Fanuc => "G21",
+            NcDialect::Siemens => "G71",
+        }
+    }
+
+    fn feed_per_revolution(&self) -> &'static str {
+        match self.0 {
+            NcDialect::Fanuc => "G99",
+            NcDialect::Siemens => "G95",
+        }
+    }
+
+    fn feed_per_minute(&self) -> &'static str {
+        match self.0 {
+            NcDialect::Fanuc => "G98",
+            NcDialect::Siemens => "G94",
+        }
+    }
+}
+
+/// Writes the bar pull subprogram: grip the bar with the claw, open the chuck, pull the bar out
+/// by the pull distance, close the chuck, face and part off.
+///
+/// Positions are taken from the three claw view, where the bar is drawn after the pull and
+/// measured from its front face towards the chuck. The program Z-zero lies `z_zero` behind the
+/// front face, so a position `p` is programmed as `Z = z_zero - p`. X is a diameter. The parting
+/// tool is programmed on its chuck side edge.
+pub fn bar_pull_program(
+    fields: &BarPullProgramFields,
+    m_codes: &ChuckMCodes,
+    three_claw_fields: &ThreeClawPullingFields,
+    material_fields: &MaterialFields,
+    cutting_tool_fields: &CuttingToolFields,
+    workpiece_fields: &WorkpieceFields,
+) -> String {
+    let dialect = Dialect(fields.dialect);
+    let result = &three_claw_fields.result;
+    let z = |position: f32| format!("Z{:.3}", three_claw_fields.z_zero - position);
+    let safe_x = format!("X{:.3}", material_fields.diameter + 2.0 * fields.clearance);
+    let mut program = String::new();
+    let mut line = |text: String| {
+        let _ = writeln!(program, "{}", text);
+    };
+
+    match fields.dialect {
+        NcDialect::Fanuc => {
+            line("%".to_string());
+            line(format!(
+                "O{:04} {}",
+                fields.program_number,
+                dialect.comment("BAR PULL")
+            ));
+        }
+        NcDialect::Siemens => {
+            line(format!("%_N_BAR_PULL_{}_SPF", fields.program_number));
+            line(";$PATH=/_N_SPF_DIR".to_string());
+        }
+    }
+    line(dialect.comment(&format!(
+        "PULL {:.3} PART LENGTH {:.3} PARTING WIDTH {:.3}",
+        result.pull_distance, workpiece_fields.length, cutting_tool_fields.width
+    )));
+    line(format!("G0 G40 {}", dialect.metric()));
+    line("M5".to_string());
+
+    // Approach: the bar face sits where the last part-off left it, one pull distance in
+    line(dialect.comment("APPROACH WITH CLAW"));
+    line(dialect.tool(fields.claw_tool));
+    line(format!(
+        "G0 X0 {}",
+        z(result.pull_distance - fields.clearance)
+    ));
+    line(format!(
+        "{} G1 {} F{:.0}",
+        dialect.feed_per_minute(),
+        z(result.pull_distance + three_claw_fields.gripping_point),
+        fields.pull_feed
+    ));
+
+    line(dialect.comment("OPEN CHUCK"));
+    line(format!("M{}", m_codes.open));
+    line(dialect.dwell(fields.dwell));
+
+    line(dialect.comment("PULL BAR"));
+    line(format!(
+        "G1 {} F{:.0}",
+        z(three_claw_fields.gripping_point),
+        fields.pull_feed
+    ));
+
+    line(dialect.comment("CLOSE CHUCK"));
+    line(format!("M{}", m_codes.close));
+    line(dialect.dwell(fields.dwell));
+    line(format!("G0 {}", z(-fields.clearance)));
+
+    line(dialect.comment("FACE"));
+    line(dialect.tool(fields.facing_tool));
+    line(format!(
+        "{} G97 S{:.0} M3",
+        dialect.feed_per_revolution(),
+        fields.spindle_speed
+    ));
+    line(format!(
+        "G0 {} {}",
+        safe_x,
+        z(workpiece_fields.facing_stock_right)
+    ));
+    line(format!("G1 X-1.000 F{:.3}", fields.facing_feed));
+    line(format!("G0 {}", z(-fields.clearance)));
+    line(format!("G0 {}", safe_x));
+
+    line(dialect.comment("PART OFF"));
+    line(dialect.tool(fields.parting_tool));
+    line(format!(
+        "G0 {} {}",
+        safe_x,
+        z(result.parting_position + cutting_tool_fields.width)
+    ));
+    line(format!("G1 X-1.000 F{:.3}", fields.parting_feed));
+    line(format!("G0 {}", safe_x));
+    line("M5".to_string());
+
+    match fields.dialect {
+        NcDialect::Fanuc => {
+            line("M99".to_string());
+            line("%".to_string());
+        }
+        NcDialect::Siemens => line("RET".to_string()),
+    }
+    program
+}
+
+pub fn bar_pull_program_panel(app: &mut ManagingApp, ui: &mut egui::Ui) {
+    let mut generate = false;
+    let machine = app.selections.machine.and_then(|i| app.machines.get_mut(i));
+    let calculations = &mut app.gui_singletons.universal_calculations;
+    let fields = &mut calculations.bar_pull_program;
+
+    ui.label(egui::RichText::new("Bar Pull Program").heading());
+    ui.horizontal(|ui| {
+        for dialect in NcDialect::iter() {
+            ui.radio_value(&mut fields.dialect, dialect, dialect.to_string());
+        }
+    });
+    match &machine {
+        Some(machine) => {
+            ui.label(format!("M-codes of {}", machine.name));
+        }
+        None => {
+            ui.label("No machine selected, using default M-codes");
+        }
+    }
+    let mut default_m_codes = ChuckMCodes::default();
+    let m_codes = match machine {
+        Some(machine) => &mut machine.chuck_m_codes,
+        None => &mut default_m_codes,
+    };
+    chuck_m_codes_edit(m_codes, ui);
+    egui::Grid::new("bar_pull_program_grid")
+        .num_columns(4)
+        .min_col_width(MIN_COLUMN_WIDTH)
+        .show(ui, |ui| {
+            ui.label("Program number: ");
+            ui.add(egui::DragValue::new(&mut fields.program_number).clamp_range(1..=9999));
+            ui.label("Claw tool: T");
+            ui.add(egui::DragValue::new(&mut fields.claw_tool).clamp_range(1..=99));
+            ui.end_row();
+            ui.label("Facing tool: T");
+            ui.add(egui::DragValue::new(&mut fields.facing_tool).clamp_range(1..=99));
+            ui.label("Parting tool: T");
+            ui.add(egui::DragValue::new(&mut fields.parting_tool).clamp_range(1..=99));
+            ui.end_row();
+            ui.label("Pull feed [mm/min]: ");
+            ui.add(egui::DragValue::new(&mut fields.pull_feed).clamp_range(1.0..=10000.0));
+            ui.label("Spindle speed [rpm]: ");
+            ui.add(egui::DragValue::new(&mut fields.spindle_speed).clamp_range(1.0..=10000.0));
+            ui.end_row();
+            ui.label("Facing feed [mm/rev]: ");
+            ui.add(
+                egui::DragValue::new(&mut fields.facing_feed)
+                    .speed(0.01)
+                    .clamp_range(0.0..=2.0),
+            );
+            ui.label("Parting feed [mm/rev]: ");
+            ui.add(
+                egui::DragValue::new(&mut fields.parting_feed)
+                    .speed(0.01)
+                    .clamp_range(0.0..=2.0),
+            );
+            ui.end_row();
+            ui.label("Clearance: ");
+            ui.add(egui::DragValue::new(&mut fields.clearance).clamp_range(0.0..=50.0));
+            ui.label("Dwell [s]: ");
+            ui.add(
+                egui::DragValue::new(&mut fields.dwell)
+                    .speed(0.1)
+                    .clamp_range(0.0..=10.0),
+            );
+            ui.end_row();
+        });
+    ui.horizontal(|ui| {
+        generate = ui.button("Generate").clicked();
+        if ui.button("Copy to clipboard").clicked() {
+            ui.output_mut(|output| output.copied_text = fields.text.clone());
+        }
+    });
+    if generate {
+        fields.text = bar_pull_program(
+            fields,
+            m_codes,
+            &calculations.three_claw_pulling,
+            &calculations.material_fields,
+            &calculations.cutting_tool_fields,
+            &calculations.workpiece_fields,
+        );
+    }
+    egui::ScrollArea::vertical()
+        .id_source("bar_pull_program_text")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut calculations.bar_pull_program.text)
+                    .code_editor()
+                    .desired_width(f32::INFINITY),
+            );
+        });
+}
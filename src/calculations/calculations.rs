@@ -1,7 +1,8 @@
-use std::default;
-
+use crate::calculations::bar_pull_program::*;
+use crate::calculations::cutting_data::*;
 use crate::calculations::imperialmetricconversion::*;
 use crate::calculations::radiandegreeconversion::*;
+use crate::machine::selected_machine_type;
 use crate::three_claw_pulling::*;
 use crate::{reset_states, ManagingApp};
 use strum::{Display, EnumIter, EnumString};
@@ -26,6 +27,7 @@ pub enum CalculationType {
     ImperialMetricConversion,
     DegreeRadianConversion,
     GripClawPulling,
+    CuttingData,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
@@ -38,6 +40,10 @@ pub struct UniversalCalculations {
     pub material_fields: MaterialFields,
     pub cutting_tool_fields: CuttingToolFields,
     pub workpiece_fields: WorkpieceFields,
+    #[serde(default)]
+    pub cutting_data: CuttingDataFields,
+    #[serde(default)]
+    pub bar_pull_program: BarPullProgramFields,
 }
 
 impl Default for UniversalCalculations {
@@ -54,6 +60,8 @@ impl Default for UniversalCalculations {
             material_fields: MaterialFields::default(),
             cutting_tool_fields: CuttingToolFields::default(),
             workpiece_fields: WorkpieceFields::default(),
+            cutting_data: CuttingDataFields::default(),
+            bar_pull_program: BarPullProgramFields::default(),
         }
     }
 }
@@ -62,6 +70,8 @@ impl Default for UniversalCalculations {
 pub struct MaterialFields {
     pub diameter: f32,
     pub length: f32,
+    #[serde(default)]
+    pub grade: Option<String>, // Material from the catalogue
 }
 
 impl Default for MaterialFields {
@@ -69,6 +79,7 @@ impl Default for MaterialFields {
         Self {
             diameter: 10.0,
             length: 50.0,
+            grade: None,
         }
     }
 }
@@ -131,17 +142,25 @@ pub fn left_calculation_panel(app: &mut ManagingApp, ui: &mut egui::Ui) {
         .default_width(600.0)
         .resizable(false)
         .show_inside(ui, |ui| {
-            if ui.button("Imperial/Metric").clicked() {
-                app.gui_singletons.universal_calculations.calculation_type =
-                    CalculationType::ImperialMetricConversion;
-            }
-            if ui.button("Degree/Radian").clicked() {
-                app.gui_singletons.universal_calculations.calculation_type =
-                    CalculationType::DegreeRadianConversion;
+            let machine_type = selected_machine_type(app);
+            let offered = |calculation: &CalculationType| {
+                machine_type.map_or(true, |machine_type| {
+                    machine_type.offers_calculation(calculation)
+                })
+            };
+            let calculations = &mut app.gui_singletons.universal_calculations;
+            if !offered(&calculations.calculation_type) {
+                calculations.calculation_type = CalculationType::default();
             }
-            if ui.button("Three Claw Pulling").clicked() {
-                app.gui_singletons.universal_calculations.calculation_type =
-                    CalculationType::GripClawPulling;
+            for (calculation, label) in [
+                (CalculationType::ImperialMetricConversion, "Imperial/Metric"),
+                (CalculationType::DegreeRadianConversion, "Degree/Radian"),
+                (CalculationType::GripClawPulling, "Three Claw Pulling"),
+                (CalculationType::CuttingData, "Cutting Data"),
+            ] {
+                if offered(&calculation) && ui.button(label).clicked() {
+                    calculations.calculation_type = calculation;
+                }
             }
         });
 }
@@ -167,5 +186,8 @@ pub fn handle_calculation_state_transitions(app: &mut ManagingApp, ui: &mut egui
         CalculationType::GripClawPulling => {
             handle_three_claw_pulling(app, ui);
         }
+        CalculationType::CuttingData => {
+            handle_cutting_data(app, ui);
+        }
     }
 }
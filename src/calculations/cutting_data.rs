@@ -0,0 +1,559 @@
+use std::f32::consts::PI;
+
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::custom_widgets::*;
+use crate::geometry::ToolGeometry;
+use crate::material::*;
+use crate::tool::ToolCategory;
+use crate::ManagingApp;
+
+use labeled_drag_value_widget::LabeledDragValueWidget;
+
+use super::calculations::MIN_COLUMN_WIDTH;
+
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+)]
+pub enum CuttingOperation {
+    #[default]
+    Milling,
+    Turning,
+}
+
+/// Which of cutting speed and spindle speed is entered, the other one is calculated.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum SpeedInput {
+    #[default]
+    CuttingSpeed,
+    SpindleSpeed,
+}
+
+/// Which feed quantity is entered. The other two are calculated from it.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum FeedInput {
+    #[default]
+    FeedPerTooth,
+    FeedRate,
+    RemovalRate,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct CuttingDataFields {
+    pub operation: CuttingOperation,
+    pub speed_input: SpeedInput,
+    pub feed_input: FeedInput,
+    pub diameter: f32, // Tool diameter when milling, workpiece diameter when turning [mm]
+    pub cutting_speed: f32, // Vc [m/min]
+    pub spindle_speed: f32, // n [rpm]
+    pub teeth: u32,    // z, milling only
+    pub feed_per_tooth: f32, // fz when milling, fn (feed per revolution) when turning [mm]
+    pub feed_rate: f32, // vf [mm/min]
+    pub width_of_cut: f32, // ae, milling only [mm]
+    pub depth_of_cut: f32, // ap [mm]
+    pub removal_rate: f32, // Q [cm³/min]
+    pub slot: Option<usize>, // Magazine slot the diameter was taken from
+    #[serde(default)]
+    pub tool_material: CuttingToolMaterial,
+}
+
+impl Default for CuttingDataFields {
+    fn default() -> Self {
+        let mut fields = Self {
+            operation: CuttingOperation::Milling,
+            speed_input: SpeedInput::CuttingSpeed,
+            feed_input: FeedInput::FeedPerTooth,
+            diameter: 10.0,
+            cutting_speed: 120.0,
+            spindle_speed: 0.0,
+            teeth: 4,
+            feed_per_tooth: 0.05,
+            feed_rate: 0.0,
+            width_of_cut: 5.0,
+            depth_of_cut: 10.0,
+            removal_rate: 0.0,
+            slot: None,
+            tool_material: CuttingToolMaterial::Carbide,
+        };
+        solve_cutting_data(&mut fields);
+        fields
+    }
+}
+
+/// n [rpm] from Vc [m/min] and diameter [mm].
+pub fn spindle_speed(cutting_speed: f32, diameter: f32) -> f32 {
+    if diameter <= 0.0 {
+        return 0.0;
+    }
+    cutting_speed * 1000.0 / (PI * diameter)
+}
+
+/// Vc [m/min] from n [rpm] and diameter [mm].
+pub fn cutting_speed(spindle_speed: f32, diameter: f32) -> f32 {
+    PI * diameter * spindle_speed / 1000.0
+}
+
+/// vf [mm/min] = fz * z * n when milling, fn * n when turning.
+pub fn feed_rate(operation: CuttingOperation, feed: f32, teeth: u32, spindle_speed: f32) -> f32 {
+    match operation {
+        CuttingOperation::Milling => feed * teeth as f32 * spindle_speed,
+        CuttingOperation::Turning => feed * spindle_speed,
+    }
+}
+
+/// fz or fn [mm] from vf [mm/min], the inverse of `feed_rate`.
+pub fn feed_from_feed_rate(
+    operation: CuttingOperation,
+    feed_rate: f32,
+    teeth: u32,
+    spindle_speed: f32,
+) -> f32 {
+    let per_revolution = match operation {
+        CuttingOperation::Milling => teeth as f32 * spindle_speed,
+        CuttingOperation::Turning => spindle_speed,
+    };
+    if per_revolution <= 0.0 {
+        return 0.0;
+    }
+    feed_rate / per_revolution
+}
+
+/// Q [cm³/min] = ae * ap * vf / 1000 when milling, Vc * ap * fn when turning.
+pub fn removal_rate(fields: &CuttingDataFields) -> f32 {
+    match fields.operation {
+        CuttingOperation::Milling => {
+            fields.width_of_cut * fields.depth_of_cut * fields.feed_rate / 1000.0
+        }
+        CuttingOperation::Turning => {
+            fields.cutting_speed * fields.depth_of_cut * fields.feed_per_tooth
+        }
+    }
+}
+
+/// vf [mm/min] giving the removal rate in `fields`, the inverse of `removal_rate`.
+pub fn feed_rate_from_removal_rate(fields: &CuttingDataFields) -> f32 {
+    match fields.operation {
+        CuttingOperation::Milling => {
+            let area = fields.width_of_cut * fields.depth_of_cut;
+            if area <= 0.0 {
+                return 0.0;
+            }
+            fields.removal_rate * 1000.0 / area
+        }
+        CuttingOperation::Turning => {
+            let per_revolution = fields.cutting_speed * fields.depth_of_cut;
+            if per_revolution <= 0.0 {
+                return 0.0;
+            }
+            fields.removal_rate / per_revolution * fields.spindle_speed
+        }
+    }
+}
+
+/// Calculates the values that are not inputs from the ones that are.
+pub fn solve_cutting_data(fields: &mut CuttingDataFields) {
+    match fields.speed_input {
+        SpeedInput::CuttingSpeed => {
+            fields.spindle_speed = spindle_speed(fields.cutting_speed, fields.diameter)
+        }
+        SpeedInput::SpindleSpeed => {
+            fields.cutting_speed = cutting_speed(fields.spindle_speed, fields.diameter)
+        }
+    }
+    let (operation, teeth, n) = (fields.operation, fields.teeth, fields.spindle_speed);
+    match fields.feed_input {
+        FeedInput::FeedPerTooth => {
+            fields.feed_rate = feed_rate(operation, fields.feed_per_tooth, teeth, n);
+            fields.removal_rate = removal_rate(fields);
+        }
+        FeedInput::FeedRate => {
+            fields.feed_per_tooth = feed_from_feed_rate(operation, fields.feed_rate, teeth, n);
+            fields.removal_rate = removal_rate(fields);
+        }
+        FeedInput::RemovalRate => {
+            fields.feed_rate = feed_rate_from_removal_rate(fields);
+            fields.feed_per_tooth = feed_from_feed_rate(operation, fields.feed_rate, teeth, n);
+        }
+    }
+}
+
+/// Average chip thickness h [mm]. Milling uses hm = fz * sqrt(ae / D), turning assumes a 90°
+/// entering angle so that h = fn.
+pub fn chip_thickness(fields: &CuttingDataFields) -> f32 {
+    match fields.operation {
+        CuttingOperation::Milling => {
+            if fields.diameter <= 0.0 {
+                return 0.0;
+            }
+            let engagement = (fields.width_of_cut / fields.diameter).clamp(0.0, 1.0);
+            fields.feed_per_tooth * engagement.sqrt()
+        }
+        CuttingOperation::Turning => fields.feed_per_tooth,
+    }
+}
+
+/// Cutting power Pc [kW] for a specific cutting force kc [N/mm²], Q * kc / 60000.
+pub fn cutting_power(fields: &CuttingDataFields, specific_cutting_force: f32) -> f32 {
+    fields.removal_rate * specific_cutting_force / 60000.0
+}
+
+/// Drag value for an input, plain label for a calculated value.
+fn value_row(ui: &mut egui::Ui, label: &str, hover_text: &str, value: &mut f32, is_input: bool) {
+    if is_input {
+        LabeledDragValueWidget::new(label, value)
+            .hover_text(hover_text)
+            .show(ui);
+    } else {
+        ui.label(label).on_hover_text(hover_text);
+        ui.strong(format!("{:.3}", value));
+    }
+    ui.end_row();
+}
+
+pub fn handle_cutting_data(app: &mut ManagingApp, ui: &mut egui::Ui) {
+    let loaded_rotating_tools: Vec<(usize, String, f32, Option<ToolGeometry>)> = app
+        .display_magazine
+        .contents
+        .iter()
+        .filter_map(|slot| slot.tool.as_ref().map(|tool| (slot.pocket, tool)))
+        .filter(|(_, tool)| tool.get_category() == ToolCategory::Rotating)
+        .map(|(pocket, tool)| {
+            (
+                pocket,
+                tool.get_name(),
+                tool.get_diameter(),
+                tool.get_geometry().cloned(),
+            )
+        })
+        .collect();
+    let limits = app
+        .selections
+        .machine
+        .and_then(|i| app.machines.get(i))
+        .map(|machine| machine.limits.clone())
+        .unwrap_or_default();
+    let calculations = &mut app.gui_singletons.universal_calculations;
+    let fields = &mut calculations.cutting_data;
+    let grade = &mut calculations.material_fields.grade;
+
+    ui.label(egui::RichText::new("Cutting Data").heading());
+    ui.horizontal(|ui| {
+        for operation in CuttingOperation::iter() {
+            ui.radio_value(&mut fields.operation, operation, operation.to_string());
+        }
+    });
+    if fields.operation == CuttingOperation::Milling {
+        let selected_text = fields
+            .slot
+            .and_then(|pocket| loaded_rotating_tools.iter().find(|(p, ..)| *p == pocket))
+            .map(|(pocket, name, ..)| format!("Slot {}: {}", pocket, name))
+            .unwrap_or("Select tool".to_string());
+        egui::ComboBox::from_label("Tool from magazine")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (pocket, name, diameter, geometry) in loaded_rotating_tools.iter() {
+                    if ui
+                        .selectable_label(
+                            fields.slot == Some(*pocket),
+                            format!("Slot {}: {} Ø{}", pocket, name, diameter),
+                        )
+                        .clicked()
+                    {
+                        fields.slot = Some(*pocket);
+                        fields.diameter = *diameter;
+                        // Flutes and substrate of solid tools fill in z and the Vc column
+                        if let Some(geometry) = geometry {
+                            fields.teeth = geometry.flutes;
+                            if let Some(material) = geometry.material.cutting_data_material() {
+                                fields.tool_material = material;
+                            }
+                        }
+                    }
+                }
+            });
+    }
+    select_material(ui, "Workpiece material", &app.materials, grade);
+    let material = grade.as_ref().and_then(|grade| app.materials.get(grade));
+    ui.horizontal(|ui| {
+        ui.label("Tool material:");
+        for tool_material in CuttingToolMaterial::iter() {
+            ui.radio_value(
+                &mut fields.tool_material,
+                tool_material,
+                tool_material.to_string(),
+            );
+        }
+    });
+    if let Some(material) = material {
+        ui.horizontal(|ui| match material.vc_range(fields.tool_material) {
+            Some(range) => {
+                ui.label(format!(
+                    "Recommended Vc: {:.0} - {:.0} m/min",
+                    range.min, range.max
+                ));
+                if ui
+                    .button("Use")
+                    .on_hover_text("Set Vc to the middle of the range")
+                    .clicked()
+                {
+                    fields.speed_input = SpeedInput::CuttingSpeed;
+                    fields.cutting_speed = (range.min + range.max) * 0.5;
+                }
+            }
+            None => {
+                ui.label(format!(
+                    "No recommended Vc for {} in {}",
+                    fields.tool_material, material.grade
+                ));
+            }
+        });
+    }
+    ui.horizontal(|ui| {
+        ui.label("Calculate from:");
+        ui.radio_value(&mut fields.speed_input, SpeedInput::CuttingSpeed, "Vc");
+        ui.radio_value(&mut fields.speed_input, SpeedInput::SpindleSpeed, "n");
+        ui.separator();
+        let feed_label = match fields.operation {
+            CuttingOperation::Milling => "fz",
+            CuttingOperation::Turning => "fn",
+        };
+        for (input, label) in FeedInput::iter().zip([feed_label, "vf", "Q"]) {
+            ui.radio_value(&mut fields.feed_input, input, label);
+        }
+    });
+    ui.separator();
+
+    let milling = fields.operation == CuttingOperation::Milling;
+    egui::Grid::new("cutting_data_grid")
+        .num_columns(2)
+        .min_col_width(MIN_COLUMN_WIDTH)
+        .show(ui, |ui| {
+            let diameter_hover = if milling {
+                "Tool diameter"
+            } else {
+                "Workpiece diameter at the cut"
+            };
+            value_row(ui, "D [mm]: ", diameter_hover, &mut fields.diameter, true);
+            let speed_input = fields.speed_input;
+            value_row(
+                ui,
+                "Vc [m/min]: ",
+                "Cutting speed",
+                &mut fields.cutting_speed,
+                speed_input == SpeedInput::CuttingSpeed,
+            );
+            value_row(
+                ui,
+                "n [rpm]: ",
+                "Spindle speed",
+                &mut fields.spindle_speed,
+                speed_input == SpeedInput::SpindleSpeed,
+            );
+            if milling {
+                ui.label("z: ").on_hover_text("Number of teeth");
+                ui.add(egui::DragValue::new(&mut fields.teeth).clamp_range(1..=100));
+                ui.end_row();
+            }
+            let feed_input = fields.feed_input;
+            let (feed_label, feed_hover) = if milling {
+                ("fz [mm]: ", "Feed per tooth")
+            } else {
+                ("fn [mm/rev]: ", "Feed per revolution")
+            };
+            value_row(
+                ui,
+                feed_label,
+                feed_hover,
+                &mut fields.feed_per_tooth,
+                feed_input == FeedInput::FeedPerTooth,
+            );
+            value_row(
+                ui,
+                "vf [mm/min]: ",
+                "Feed rate",
+                &mut fields.feed_rate,
+                feed_input == FeedInput::FeedRate,
+            );
+            if milling {
+                value_row(
+                    ui,
+                    "ae [mm]: ",
+                    "Width of cut",
+                    &mut fields.width_of_cut,
+                    true,
+                );
+            }
+            value_row(
+                ui,
+                "ap [mm]: ",
+                "Depth of cut",
+                &mut fields.depth_of_cut,
+                true,
+            );
+            value_row(
+                ui,
+                "Q [cm³/min]: ",
+                "Material removal rate",
+                &mut fields.removal_rate,
+                feed_input == FeedInput::RemovalRate,
+            );
+        });
+    solve_cutting_data(fields);
+    if let Some(max_rpm) = limits.max_rpm.filter(|max| fields.spindle_speed > *max) {
+        ui.colored_label(
+            egui::Color32::RED,
+            format!("n exceeds the machine maximum of {:.0} rpm", max_rpm),
+        );
+    }
+    if let Some(material) = material {
+        ui.separator();
+        let chip_thickness = chip_thickness(fields);
+        let specific_cutting_force = material.specific_cutting_force(chip_thickness);
+        egui::Grid::new("cutting_force_grid")
+            .num_columns(2)
+            .min_col_width(MIN_COLUMN_WIDTH)
+            .show(ui, |ui| {
+                ui.label("h [mm]: ").on_hover_text("Average chip thickness");
+                ui.strong(format!("{:.4}", chip_thickness));
+                ui.end_row();
+                ui.label("kc [N/mm²]: ")
+                    .on_hover_text("Specific cutting force, kc1.1 * h^-mc");
+                ui.strong(format!("{:.0}", specific_cutting_force));
+                ui.end_row();
+                let power = cutting_power(fields, specific_cutting_force);
+                ui.label("Pc [kW]: ").on_hover_text("Cutting power");
+                ui.strong(format!("{:.2}", power));
+                ui.end_row();
+                if let Some(max_power) = limits.power.filter(|max| power > *max) {
+                    ui.label("");
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Exceeds the {:.1} kW spindle power", max_power),
+                    );
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() <= b.abs() * 1e-4 + 1e-6, "{} != {}", a, b);
+    }
+
+    // Clears whatever the solve calculates, so a value it fails to compute cannot pass
+    fn solved(mut fields: CuttingDataFields) -> CuttingDataFields {
+        match fields.speed_input {
+            SpeedInput::CuttingSpeed => fields.spindle_speed = 0.0,
+            SpeedInput::SpindleSpeed => fields.cutting_speed = 0.0,
+        }
+        match fields.feed_input {
+            FeedInput::FeedPerTooth => (fields.feed_rate, fields.removal_rate) = (0.0, 0.0),
+            FeedInput::FeedRate => (fields.feed_per_tooth, fields.removal_rate) = (0.0, 0.0),
+            FeedInput::RemovalRate => (fields.feed_per_tooth, fields.feed_rate) = (0.0, 0.0),
+        }
+        solve_cutting_data(&mut fields);
+        fields
+    }
+
+    // Ø10 four flute mill at 3000 rpm, fz 0.1, ae 5, ap 10: vf 1200 mm/min, Q 60 cm³/min
+    fn milling(speed_input: SpeedInput, feed_input: FeedInput) -> CuttingDataFields {
+        solved(CuttingDataFields {
+            operation: CuttingOperation::Milling,
+            speed_input,
+            feed_input,
+            diameter: 10.0,
+            cutting_speed: 94.24778,
+            spindle_speed: 3000.0,
+            teeth: 4,
+            feed_per_tooth: 0.1,
+            feed_rate: 1200.0,
+            width_of_cut: 5.0,
+            depth_of_cut: 10.0,
+            removal_rate: 60.0,
+            ..CuttingDataFields::default()
+        })
+    }
+
+    // Ø50 bar at Vc 200, fn 0.2, ap 2: n 1273.24 rpm, vf 254.65 mm/min, Q 80 cm³/min
+    fn turning(feed_input: FeedInput) -> CuttingDataFields {
+        solved(CuttingDataFields {
+            operation: CuttingOperation::Turning,
+            speed_input: SpeedInput::CuttingSpeed,
+            feed_input,
+            diameter: 50.0,
+            cutting_speed: 200.0,
+            feed_per_tooth: 0.2,
+            feed_rate: 254.6479,
+            depth_of_cut: 2.0,
+            removal_rate: 80.0,
+            ..CuttingDataFields::default()
+        })
+    }
+
+    #[test]
+    fn milling_speeds_solve_both_ways() {
+        let fields = milling(SpeedInput::SpindleSpeed, FeedInput::FeedPerTooth);
+        assert_near(fields.cutting_speed, 94.24778);
+        let fields = milling(SpeedInput::CuttingSpeed, FeedInput::FeedPerTooth);
+        assert_near(fields.spindle_speed, 3000.0);
+    }
+
+    #[test]
+    fn milling_feeds_solve_from_each_input() {
+        for feed_input in FeedInput::iter() {
+            let fields = milling(SpeedInput::SpindleSpeed, feed_input);
+            assert_near(fields.feed_per_tooth, 0.1);
+            assert_near(fields.feed_rate, 1200.0);
+            assert_near(fields.removal_rate, 60.0);
+        }
+    }
+
+    #[test]
+    fn turning_feeds_solve_from_each_input() {
+        for feed_input in FeedInput::iter() {
+            let fields = turning(feed_input);
+            assert_near(fields.spindle_speed, 1273.2395);
+            assert_near(fields.feed_per_tooth, 0.2);
+            assert_near(fields.feed_rate, 254.6479);
+            assert_near(fields.removal_rate, 80.0);
+        }
+    }
+
+    #[test]
+    fn chip_thickness_and_power() {
+        let fields = milling(SpeedInput::SpindleSpeed, FeedInput::FeedPerTooth);
+        // Half the diameter engaged: hm = fz * sqrt(0.5)
+        assert_near(chip_thickness(&fields), 0.07071068);
+        assert_near(cutting_power(&fields, 2000.0), 2.0);
+        let fields = turning(FeedInput::FeedPerTooth);
+        assert_near(chip_thickness(&fields), 0.2);
+        assert_near(cutting_power(&fields, 1500.0), 2.0);
+    }
+
+    #[test]
+    fn zero_diameter_gives_zero_instead_of_infinity() {
+        let mut fields = milling(SpeedInput::CuttingSpeed, FeedInput::FeedRate);
+        fields.diameter = 0.0;
+        solve_cutting_data(&mut fields);
+        assert_eq!(fields.spindle_speed, 0.0);
+        assert_eq!(fields.feed_per_tooth, 0.0);
+        assert_eq!(chip_thickness(&fields), 0.0);
+    }
+}
@@ -234,7 +234,7 @@ pub fn handle_length_conversion(
     ui.heading("Length Conversion");
     ui.horizontal(|ui| {
         egui::ComboBox::from_label("Length Metric Unit")
-            .selected_text(&conversion.metric_unit_length.to_string())
+            .selected_text(conversion.metric_unit_length.to_string())
             .show_ui(ui, |ui| {
                 ui.selectable_value(
                     &mut conversion.metric_unit_length,
@@ -253,7 +253,7 @@ pub fn handle_length_conversion(
                 );
             });
         egui::ComboBox::from_label("Length Imperial Unit")
-            .selected_text(&conversion.imperial_unit_length.to_string())
+            .selected_text(conversion.imperial_unit_length.to_string())
             .show_ui(ui, |ui| {
                 ui.selectable_value(
                     &mut conversion.imperial_unit_length,
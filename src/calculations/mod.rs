@@ -1,4 +1,7 @@
+pub mod bar_pull_program;
+#[allow(clippy::module_inception)]
 pub mod calculations;
+pub mod cutting_data;
 pub mod imperialmetricconversion;
 pub mod radiandegreeconversion;
 pub mod three_claw_pulling;
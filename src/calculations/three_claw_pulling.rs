@@ -1,4 +1,6 @@
+use crate::bar_pull_program::*;
 use crate::custom_widgets::*;
+use crate::material::*;
 use crate::ManagingApp;
 use egui::{Color32, Pos2};
 
@@ -21,6 +23,8 @@ pub const LINE_WIDTH: f32 = 25.0;
 pub const TEXT_OFFSET: f32 = 20.0;
 pub const ARROW_WIDTH: f32 = 3.0;
 pub const ARROW_TOP_OFFSET: f32 = 40.0;
+pub const PULL_COLOR: Color32 = Color32::GREEN;
+pub const REMNANT_COLOR: Color32 = Color32::GRAY;
 use super::calculations::{
     CuttingToolFields, MaterialFields, WorkpieceFields, FRAME_WIDTH, MIN_COLUMN_WIDTH,
 };
@@ -32,6 +36,30 @@ pub struct ThreeClawPullingFields {
     pub gripping_point: f32,     // Point where the claw grips the material
     pub claw_overextension: f32, // Distance the claw extends past gripping point towards chuck
     pub scaling_factor: f32,     // Visualization scaling factor
+    #[serde(default)]
+    pub minimum_remnant: f32, // Bar length the chuck needs to hold the last part
+    #[serde(skip)]
+    pub result: ThreeClawPullingResult,
+}
+
+/// Outcome of `calculate_three_claw_pulling`. Positions are measured like the inputs, from the
+/// front face of the bar towards the chuck.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThreeClawPullingResult {
+    pub pull_distance: f32, // Bar consumed per part: facing stock, workpiece and parting width
+    pub new_z_zero: f32,    // Z-zero of the next part before the pull
+    pub claw_reach: f32,    // Gripping point plus claw overextension and safety margin
+    pub parting_position: f32, // Where the parting tool starts cutting
+    pub collision: bool,    // Claw reach passes the parting position
+    pub parts: u32,
+    pub remnant: f32,
+}
+
+/// Weight [kg] of a round bar of the selected material.
+pub fn bar_weight(material_fields: &MaterialFields, material: &Material) -> f32 {
+    let volume =
+        std::f32::consts::PI * 0.25 * material_fields.diameter.powi(2) * material_fields.length;
+    material.density * volume / 1_000_000.0
 }
 
 pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
@@ -70,6 +98,14 @@ pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
             .hover_text("Desired safety margin past claw overextension")
             .show(ui);
             ui.end_row();
+            let (_response, _) = LabeledDragValueWidget::new(
+                "Minimum remnant: ",
+                &mut three_claw_fields.minimum_remnant,
+            )
+            .color(REMNANT_COLOR)
+            .hover_text("Bar length the chuck needs to hold the last part")
+            .show(ui);
+            ui.end_row();
             let (response, _) = LabeledDragValueWidget::new(
                 "Gripping point: ",
                 &mut three_claw_fields.gripping_point,
@@ -94,6 +130,8 @@ pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
         .show(ui, |ui| {
             ui.label(egui::RichText::new("Material").heading());
             ui.end_row();
+            select_material(ui, "Grade", &app.materials, &mut material_fields.grade);
+            ui.end_row();
             let (_response, _) =
                 LabeledDragValueWidget::new("Diameter: ", &mut material_fields.diameter)
                     .color(BAR_COLOR)
@@ -106,6 +144,15 @@ pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
                     .hover_text("Length of the bar")
                     .show(ui);
             ui.end_row();
+            if let Some(material) = material_fields
+                .grade
+                .as_ref()
+                .and_then(|grade| app.materials.get(grade))
+            {
+                ui.label("Bar weight: ");
+                ui.label(format!("{:.2} kg", bar_weight(material_fields, material)));
+                ui.end_row();
+            }
             if response.changed() {
                 calculate_three_claw_pulling(
                     three_claw_fields,
@@ -191,6 +238,48 @@ pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
             ui.end_row();
         });
 
+    calculate_three_claw_pulling(
+        three_claw_fields,
+        material_fields,
+        cutting_tool_fields,
+        workpiece_fields,
+    );
+    let result = three_claw_fields.result.clone();
+    ui.separator();
+    egui::Grid::new("three_claw_result_grid")
+        .num_columns(2)
+        .min_col_width(MIN_COLUMN_WIDTH)
+        .show(ui, |ui| {
+            ui.label(egui::RichText::new("Result").heading());
+            ui.end_row();
+            ui.colored_label(PULL_COLOR, "Pull distance: ")
+                .on_hover_text("Facing stock, workpiece and cutting tool width");
+            ui.label(format!("{:.2}", result.pull_distance));
+            ui.end_row();
+            ui.colored_label(Z_ZERO_COLOR, "Next Z Zero: ")
+                .on_hover_text("Z zero of the next part before the pull");
+            ui.label(format!("{:.2}", result.new_z_zero));
+            ui.end_row();
+            ui.label("Parts from bar: ");
+            ui.label(result.parts.to_string());
+            ui.end_row();
+            ui.colored_label(REMNANT_COLOR, "Remnant: ");
+            ui.label(format!("{:.2}", result.remnant));
+            ui.end_row();
+            ui.label("Claw clearance: ")
+                .on_hover_text("Distance between claw reach and the parting tool");
+            let clearance = result.parting_position - result.claw_reach;
+            if result.collision {
+                ui.colored_label(
+                    Color32::RED,
+                    format!("{:.2} - claw collides with cutting tool", clearance),
+                );
+            } else {
+                ui.label(format!("{:.2}", clearance));
+            }
+            ui.end_row();
+        });
+
     let drawing_frame = DrawingFrame::new(FRAME_WIDTH, FRAME_HEIGHT);
     let offset_from_right: f32 = 10.0;
     let offset_from_left: f32 = 10.0;
@@ -335,13 +424,151 @@ pub fn handle_three_claw_pulling(app: &mut ManagingApp, ui: &mut egui::Ui) {
         Z_ZERO_COLOR,
     );
 
+    // Pull distance above the claw
+    let y_pull = y_above - LINE_WIDTH;
+    drawing_frame.add_line(
+        Pos2::new(bar_start.x, y_pull),
+        Pos2::new(
+            bar_start.x - result.pull_distance * three_claw_fields.scaling_factor,
+            y_pull,
+        ),
+        PULL_COLOR,
+        format!("Pull distance: {:.2}", result.pull_distance),
+        ARROW_WIDTH,
+        TEXT_OFFSET,
+    );
+
+    // Remnant at the chuck end of the bar
+    drawing_frame.add_line(
+        Pos2::new(bar_end.x, y_below),
+        Pos2::new(
+            bar_end.x + result.remnant * three_claw_fields.scaling_factor,
+            y_below,
+        ),
+        REMNANT_COLOR,
+        format!("Remnant: {:.2}", result.remnant),
+        LINE_WIDTH,
+        TEXT_OFFSET,
+    );
+
+    let next_zero_point = bar_start.x - (result.new_z_zero * three_claw_fields.scaling_factor);
+    drawing_frame.add_arrow(
+        Pos2::new(next_zero_point, ARROW_TOP_OFFSET),
+        Pos2::new(next_zero_point, bar_y),
+        Z_ZERO_COLOR,
+        format!("Next Z-Zero: {:.2}", result.new_z_zero),
+        ARROW_WIDTH,
+        TEXT_OFFSET,
+    );
+
+    if result.collision {
+        drawing_frame.add_text(
+            "Claw collides with cutting tool".to_string(),
+            Pos2::new(offset_from_left, drawing_frame.size.y - 2.0 * TEXT_OFFSET),
+            Color32::RED,
+        );
+    }
+
     ui.add(drawing_frame);
+    ui.separator();
+    bar_pull_program_panel(app, ui);
 }
 
 pub fn calculate_three_claw_pulling(
-    _three_claw_fields: &mut ThreeClawPullingFields,
-    _material_fields: &mut MaterialFields,
-    _cutting_tool_fields: &mut CuttingToolFields,
-    _workpiece_fields: &mut WorkpieceFields,
+    three_claw_fields: &mut ThreeClawPullingFields,
+    material_fields: &mut MaterialFields,
+    cutting_tool_fields: &mut CuttingToolFields,
+    workpiece_fields: &mut WorkpieceFields,
 ) {
+    let parting_position = workpiece_fields.facing_stock_right
+        + workpiece_fields.length
+        + workpiece_fields.facing_stock_left;
+    let pull_distance = parting_position + cutting_tool_fields.width;
+    let claw_reach = three_claw_fields.gripping_point
+        + three_claw_fields.claw_overextension
+        + three_claw_fields.desired_safety_margin_past_claw_overextension;
+    let usable_length = (material_fields.length - three_claw_fields.minimum_remnant).max(0.0);
+    let parts = if pull_distance > 0.0 {
+        (usable_length / pull_distance).floor() as u32
+    } else {
+        0
+    };
+    three_claw_fields.result = ThreeClawPullingResult {
+        pull_distance,
+        new_z_zero: three_claw_fields.z_zero + pull_distance,
+        claw_reach,
+        parting_position,
+        collision: claw_reach > parting_position,
+        parts,
+        remnant: material_fields.length - parts as f32 * pull_distance,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 40 mm parts with 1 mm facing stock on each side and a 3 mm parting blade: 45 mm per part,
+    // parting starts 42 mm behind the front face. The claw reaches 35 mm.
+    fn pull(bar_length: f32, minimum_remnant: f32, gripping_point: f32) -> ThreeClawPullingResult {
+        let mut three_claw_fields = ThreeClawPullingFields {
+            z_zero: 2.0,
+            desired_safety_margin_past_claw_overextension: 5.0,
+            gripping_point,
+            claw_overextension: 10.0,
+            minimum_remnant,
+            ..ThreeClawPullingFields::default()
+        };
+        calculate_three_claw_pulling(
+            &mut three_claw_fields,
+            &mut MaterialFields {
+                length: bar_length,
+                ..MaterialFields::default()
+            },
+            &mut CuttingToolFields { width: 3.0 },
+            &mut WorkpieceFields {
+                length: 40.0,
+                facing_stock_right: 1.0,
+                facing_stock_left: 1.0,
+            },
+        );
+        three_claw_fields.result
+    }
+
+    #[test]
+    fn counts_parts_and_remnant() {
+        let result = pull(1000.0, 50.0, 20.0);
+        assert_eq!(result.pull_distance, 45.0);
+        assert_eq!(result.new_z_zero, 47.0);
+        assert_eq!(result.parting_position, 42.0);
+        // 950 mm usable, 21 parts use 945 mm
+        assert_eq!(result.parts, 21);
+        assert_eq!(result.remnant, 55.0);
+    }
+
+    #[test]
+    fn exact_fit_leaves_the_minimum_remnant() {
+        let result = pull(500.0, 50.0, 20.0);
+        assert_eq!(result.parts, 10);
+        assert_eq!(result.remnant, 50.0);
+    }
+
+    #[test]
+    fn bar_shorter_than_the_remnant_gives_no_parts() {
+        let result = pull(40.0, 50.0, 20.0);
+        assert_eq!(result.parts, 0);
+        assert_eq!(result.remnant, 40.0);
+    }
+
+    #[test]
+    fn claw_reaching_past_the_parting_position_collides() {
+        let result = pull(1000.0, 50.0, 20.0);
+        assert_eq!(result.claw_reach, 35.0);
+        assert!(!result.collision);
+        // Reaching exactly to the parting position is still clear
+        assert!(!pull(1000.0, 50.0, 27.0).collision);
+        let result = pull(1000.0, 50.0, 30.0);
+        assert_eq!(result.claw_reach, 45.0);
+        assert!(result.collision);
+    }
 }
@@ -97,7 +97,7 @@ pub fn square_feet_to_square_meters(feet: f32) -> f32 {
 }
 
 pub fn square_yards_to_square_millimeters(yards: f32) -> f32 {
-    yards * 836127.36
+    yards * 836_127.4
 }
 
 pub fn square_yards_to_square_centimeters(yards: f32) -> f32 {
@@ -117,7 +117,7 @@ pub fn square_millimeters_to_square_feet(mm: f32) -> f32 {
 }
 
 pub fn square_millimeters_to_square_yards(mm: f32) -> f32 {
-    mm / 836127.36
+    mm / 836_127.4
 }
 
 pub fn square_centimeters_to_square_inches(cm: f32) -> f32 {
@@ -1,14 +1,17 @@
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct Comment {
     pub comment: String,
 }
 
-impl Comment {
-    pub fn default() -> Self {
+impl Default for Comment {
+    fn default() -> Self {
         Self {
             comment: "__".to_string(),
         }
     }
+}
+
+impl Comment {
     pub fn display(&self, ui: &mut egui::Ui) {
         ui.add(egui::widgets::Label::new(&self.comment).truncate(true));
     }
@@ -0,0 +1,440 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::holder::*;
+use crate::resources::MagazineLibraryMovingSelections;
+use crate::slot::Slot;
+use crate::tool::*;
+use crate::turret::StationOrientation;
+use crate::{reset_states, ManagingApp};
+
+/// Taper or coupling between spindle, adapter and holder.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum ToolInterface {
+    #[default]
+    Sk40,
+    Sk50,
+    Bt30,
+    Bt40,
+    Bt50,
+    HskA63,
+    HskA100,
+    CaptoC5,
+    CaptoC6,
+    Vdi30,
+    Vdi40,
+    Bmt45,
+    Bmt55,
+    Bmt65,
+}
+
+impl fmt::Display for ToolInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolInterface::Sk40 => write!(f, "SK40"),
+            ToolInterface::Sk50 => write!(f, "SK50"),
+            ToolInterface::Bt30 => write!(f, "BT30"),
+            ToolInterface::Bt40 => write!(f, "BT40"),
+            ToolInterface::Bt50 => write!(f, "BT50"),
+            ToolInterface::HskA63 => write!(f, "HSK-A63"),
+            ToolInterface::HskA100 => write!(f, "HSK-A100"),
+            ToolInterface::CaptoC5 => write!(f, "Capto C5"),
+            ToolInterface::CaptoC6 => write!(f, "Capto C6"),
+            ToolInterface::Vdi30 => write!(f, "VDI30"),
+            ToolInterface::Vdi40 => write!(f, "VDI40"),
+            ToolInterface::Bmt45 => write!(f, "BMT45"),
+            ToolInterface::Bmt55 => write!(f, "BMT55"),
+            ToolInterface::Bmt65 => write!(f, "BMT65"),
+        }
+    }
+}
+
+/// Combo box for an interface, where `None` means unknown and skips validation.
+pub fn interface_combo(ui: &mut egui::Ui, label: &str, interface: &mut Option<ToolInterface>) {
+    egui::ComboBox::from_label(label)
+        .selected_text(
+            interface
+                .map(|interface| interface.to_string())
+                .unwrap_or("Unknown".to_string()),
+        )
+        .show_ui(ui, |ui| {
+            ui.selectable_value(interface, None, "Unknown");
+            for option in ToolInterface::iter() {
+                ui.selectable_value(interface, Some(option), option.to_string());
+            }
+        });
+}
+
+/// Lists why the contents of `slot` cannot be mounted together on a spindle with the given
+/// interface. Turret stations bring their own interface instead of the spindle. Values that are
+/// not known are not checked.
+pub fn mount_violations(slot: &Slot, spindle: Option<ToolInterface>) -> Vec<String> {
+    let mut violations = Vec::new();
+    let (spindle, receiver) = match &slot.station {
+        Some(station) => (station.interface, "station"),
+        None => (spindle, "spindle"),
+    };
+    if let (Some(tool), Some(station)) = (&slot.tool, &slot.station) {
+        let needs_drive = match tool {
+            Tool::Mill(_)
+            | Tool::EndMill(_)
+            | Tool::ChamferMill(_)
+            | Tool::FaceMill(_)
+            | Tool::ThreadMill(_) => true,
+            // Axial hole making tools can work on centre with the main spindle turning
+            Tool::Drill(_) | Tool::Tap(_) | Tool::Reamer(_) => {
+                station.orientation == StationOrientation::Radial
+            }
+            Tool::TrigonInsert(_) | Tool::IsoInsert(_) => false,
+        };
+        if needs_drive && !station.driven {
+            violations.push(format!(
+                "{} needs a live station, station {} is static",
+                tool.get_name(),
+                slot.pocket
+            ));
+        }
+    }
+    if let (Some(tool), Some(holder)) = (&slot.tool, &slot.holder) {
+        if tool.get_category() == ToolCategory::LatheInsert
+            && holder.get_category() != HolderCategory::TurningHolder
+        {
+            violations.push(format!(
+                "{} is an insert and needs a turning holder, {} is a {}",
+                tool.get_name(),
+                holder.get_name(),
+                holder.get_type()
+            ));
+        }
+        if let Tool::IsoInsert(insert) = tool {
+            match holder.get_insert_seat() {
+                Some(seat) => {
+                    if seat.shape != insert.shape
+                        || seat.clearance != insert.clearance
+                        || seat.size != insert.edge_length
+                    {
+                        violations.push(format!(
+                            "{} does not fit {}, which takes {}{} inserts of size {:02}",
+                            insert.code(),
+                            holder.get_name(),
+                            seat.shape.letter(),
+                            seat.clearance.letter(),
+                            seat.size
+                        ));
+                    }
+                }
+                None if holder.get_category() == HolderCategory::TurningHolder => {
+                    violations.push(format!(
+                        "{} is a {} and takes no ISO inserts",
+                        holder.get_name(),
+                        holder.get_type().to_lowercase()
+                    ));
+                }
+                None => {}
+            }
+        }
+        let pitch = match tool {
+            Tool::ThreadMill(thread_mill) => Some(thread_mill.pitch),
+            Tool::Tap(tap) => Some(tap.pitch),
+            _ => None,
+        };
+        if let (Some(pitch), Holder::ThreadingHolder(threading_holder)) = (pitch, holder) {
+            if !threading_holder.covers_pitch(pitch) {
+                let (min, max) = threading_holder.pitch_range;
+                violations.push(format!(
+                    "Pitch {} of {} is outside the range {}-{} of {}",
+                    pitch,
+                    tool.get_name(),
+                    min,
+                    max,
+                    holder.get_name()
+                ));
+            }
+        }
+        if let (Some(shank), Some((min, max))) =
+            (tool.get_shank_diameter(), holder.get_clamping_range())
+        {
+            if shank < min || shank > max {
+                violations.push(format!(
+                    "Tool shank Ø{} is outside the clamping range Ø{}-{} of {}",
+                    shank,
+                    min,
+                    max,
+                    holder.get_name()
+                ));
+            }
+        }
+    }
+    let holder_taper = slot.holder.as_ref().and_then(|holder| holder.get_taper());
+    match &slot.adapter {
+        Some(adapter) => {
+            if let (Some(taper), Some(receives)) = (holder_taper, adapter.get_holder_interface()) {
+                if taper != receives {
+                    violations.push(format!(
+                        "Holder taper {} does not fit adapter {} which takes {}",
+                        taper,
+                        adapter.get_name(),
+                        receives
+                    ));
+                }
+            }
+            if let (Some(interface), Some(spindle)) = (adapter.get_spindle_interface(), spindle) {
+                if interface != spindle {
+                    violations.push(format!(
+                        "Adapter interface {} does not fit the {} {}",
+                        interface, spindle, receiver
+                    ));
+                }
+            }
+        }
+        None => {
+            if let (Some(taper), Some(spindle)) = (holder_taper, spindle) {
+                if taper != spindle {
+                    violations.push(format!(
+                        "Holder taper {} does not fit the {} {}",
+                        taper, spindle, receiver
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+pub enum MountReview {
+    Allowed,
+    Blocked,
+    Overridden(String), // Note to record in the slot comment
+}
+
+/// Decides whether a move resulting in `candidate` may go ahead. Violations block the move
+/// and are shown by `compatibility_warning` until the user cancels or overrides them.
+pub fn review_mount(
+    selections: &mut MagazineLibraryMovingSelections,
+    candidate: &Slot,
+    spindle: Option<ToolInterface>,
+) -> MountReview {
+    review_violations(selections, mount_violations(candidate, spindle))
+}
+
+/// Like `review_mount`, for violations collected from several slots.
+pub fn review_violations(
+    selections: &mut MagazineLibraryMovingSelections,
+    violations: Vec<String>,
+) -> MountReview {
+    if violations.is_empty() {
+        selections.mount_violations.clear();
+        return MountReview::Allowed;
+    }
+    if violations != selections.mount_violations {
+        selections.mount_violations = violations;
+        selections.mount_override = false;
+    }
+    if selections.mount_override {
+        MountReview::Overridden(format!(
+            "Override: {}",
+            selections.mount_violations.join("; ")
+        ))
+    } else {
+        MountReview::Blocked
+    }
+}
+
+/// Appends an override note to the slot comment, replacing the placeholder comment.
+pub fn record_override(slot: &mut Slot, note: String) {
+    let comment = &mut slot.comment.comment;
+    if *comment == crate::comment::Comment::default().comment || comment.trim().is_empty() {
+        *comment = note;
+    } else {
+        comment.push('\n');
+        comment.push_str(&note);
+    }
+}
+
+pub fn compatibility_warning(app: &mut ManagingApp, ctx: &egui::Context) {
+    if app.move_selections.mount_violations.is_empty() {
+        return;
+    }
+    let mut cancel = false;
+    egui::Window::new("Incompatible mount")
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label("This combination cannot be mounted:");
+            for violation in app.move_selections.mount_violations.iter() {
+                ui.colored_label(egui::Color32::RED, violation);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                cancel = ui.button("Cancel").clicked();
+                if ui
+                    .button("Override")
+                    .on_hover_text("Mount anyway and note the override in the slot comment")
+                    .clicked()
+                {
+                    app.move_selections.mount_override = true;
+                }
+            });
+        });
+    if cancel {
+        reset_states(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::Adapter;
+    use crate::collet::{Collet, ErSize};
+    use crate::hydraulic::Hydraulic;
+    use crate::mill::Mill;
+    use crate::tap::Tap;
+    use crate::thread_mill::ThreadMill;
+    use crate::threading_holder::ThreadingHolder;
+
+    // Ø10 mill in an ER16 collet chuck, an ER11 one is too small for the shank
+    fn slot(er_size: ErSize, taper: Option<ToolInterface>, adapter: Option<Adapter>) -> Slot {
+        Slot {
+            tool: Some(Tool::Mill(Mill::default())),
+            holder: Some(Holder::Collet(Collet {
+                name: format!("{} collet chuck", er_size),
+                clamping_range: Some(er_size.clamping_range()),
+                taper,
+                er_size: Some(er_size),
+                ..Collet::default()
+            })),
+            adapter,
+            ..Slot::new(3)
+        }
+    }
+
+    fn adapter(
+        spindle_interface: Option<ToolInterface>,
+        holder_interface: Option<ToolInterface>,
+    ) -> Option<Adapter> {
+        Some(Adapter::Hydraulic(Hydraulic {
+            spindle_interface,
+            holder_interface,
+            ..Hydraulic::default()
+        }))
+    }
+
+    #[test]
+    fn matching_and_unknown_interfaces_pass() {
+        let sk40 = Some(ToolInterface::Sk40);
+        assert!(mount_violations(&slot(ErSize::Er16, sk40, None), sk40).is_empty());
+        assert!(mount_violations(&slot(ErSize::Er16, None, None), sk40).is_empty());
+        assert!(mount_violations(&slot(ErSize::Er16, sk40, None), None).is_empty());
+        let adapted = slot(
+            ErSize::Er16,
+            sk40,
+            adapter(Some(ToolInterface::HskA63), sk40),
+        );
+        assert!(mount_violations(&adapted, Some(ToolInterface::HskA63)).is_empty());
+    }
+
+    #[test]
+    fn shank_outside_the_clamping_range() {
+        assert_eq!(
+            mount_violations(&slot(ErSize::Er11, None, None), None),
+            ["Tool shank Ø10 is outside the clamping range Ø0.5-7 of ER11 collet chuck"]
+        );
+    }
+
+    #[test]
+    fn holder_taper_against_spindle_and_adapter() {
+        let sk40 = Some(ToolInterface::Sk40);
+        assert_eq!(
+            mount_violations(&slot(ErSize::Er16, sk40, None), Some(ToolInterface::Bt40)),
+            ["Holder taper SK40 does not fit the BT40 spindle"]
+        );
+        let adapted = slot(
+            ErSize::Er16,
+            sk40,
+            adapter(Some(ToolInterface::HskA63), Some(ToolInterface::Bt40)),
+        );
+        assert_eq!(
+            mount_violations(&adapted, Some(ToolInterface::Sk50)),
+            [
+                "Holder taper SK40 does not fit adapter Hydraulic which takes BT40",
+                "Adapter interface HSK-A63 does not fit the SK50 spindle",
+            ]
+        );
+    }
+
+    #[test]
+    fn override_is_noted_in_the_slot_comment() {
+        let mut selections = MagazineLibraryMovingSelections::default();
+        let violations = vec!["Holder taper SK40 does not fit the BT40 spindle".to_string()];
+        assert!(matches!(
+            review_violations(&mut selections, violations.clone()),
+            MountReview::Blocked
+        ));
+        selections.mount_override = true;
+        let MountReview::Overridden(note) = review_violations(&mut selections, violations) else {
+            panic!("override was not applied");
+        };
+        assert_eq!(
+            note,
+            "Override: Holder taper SK40 does not fit the BT40 spindle"
+        );
+
+        // The placeholder comment is replaced, a real one is kept above the note
+        let mut slot = Slot::new(0);
+        record_override(&mut slot, note.clone());
+        assert_eq!(slot.comment.comment, note);
+        slot.comment.comment = "Roughing".to_string();
+        record_override(&mut slot, note.clone());
+        assert_eq!(slot.comment.comment, format!("Roughing\n{}", note));
+    }
+
+    #[test]
+    fn thread_pitch_is_checked_against_threading_holders() {
+        let threading_slot = |tool: Tool| Slot {
+            tool: Some(tool),
+            holder: Some(Holder::ThreadingHolder(ThreadingHolder::default())),
+            ..Slot::new(3)
+        };
+        // The default holder covers 0.5-3.0 mm
+        let tap = Tap {
+            pitch: 1.25,
+            ..Tap::default()
+        };
+        assert!(mount_violations(&threading_slot(Tool::Tap(tap)), None).is_empty());
+        let tap = Tap {
+            pitch: 0.35,
+            ..Tap::default()
+        };
+        assert_eq!(
+            mount_violations(&threading_slot(Tool::Tap(tap)), None).len(),
+            1
+        );
+        let thread_mill = ThreadMill {
+            pitch: 4.0,
+            ..ThreadMill::default()
+        };
+        let violations = mount_violations(&threading_slot(Tool::ThreadMill(thread_mill)), None);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("0.5-3"), "{}", violations[0]);
+    }
+
+    #[test]
+    fn changed_violations_need_a_new_override() {
+        let mut selections = MagazineLibraryMovingSelections {
+            mount_violations: vec!["Old".to_string()],
+            mount_override: true,
+            ..MagazineLibraryMovingSelections::default()
+        };
+        assert!(matches!(
+            review_violations(&mut selections, vec!["New".to_string()]),
+            MountReview::Blocked
+        ));
+        assert!(matches!(
+            review_violations(&mut selections, Vec::new()),
+            MountReview::Allowed
+        ));
+        assert!(selections.mount_violations.is_empty());
+    }
+}
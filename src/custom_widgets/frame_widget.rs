@@ -1,7 +1,5 @@
-
 use egui::{Color32, FontId, Pos2, Rect, Sense, Stroke, Vec2};
 
-
 pub struct Line {
     start: Pos2,
     end: Pos2,
@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+
+use egui::{Color32, Key, KeyboardShortcut, Modifiers};
+
+use crate::adapter::Adapter;
+use crate::assembly::ToolAssembly;
+use crate::holder::Holder;
+use crate::machine::Machine;
+use crate::magazine::Magazine;
+use crate::resources::*;
+use crate::tool::Tool;
+use crate::turret::MagazineKind;
+use crate::{reset_states, ManagingApp};
+
+const MAX_ENTRIES: usize = 100;
+
+const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+/// The inventory an undo restores: machines, library and content colours.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub machines: Vec<Machine>,
+    pub tools: Vec<Tool>,
+    pub holders: Vec<Holder>,
+    pub adapters: Vec<Adapter>,
+    pub assemblies: Vec<ToolAssembly>,
+    pub colors: BTreeMap<TemplateKey, Color32>,
+}
+
+/// Template list, template type and how many templates of that type come before it. Unlike a
+/// position, the key survives templates being added to or reordered in the lists.
+type TemplateKey = (&'static str, String, usize);
+
+fn template_key(
+    seen: &mut Vec<(&'static str, String)>,
+    list: &'static str,
+    template_type: String,
+) -> TemplateKey {
+    let before = seen
+        .iter()
+        .filter(|(seen_list, seen_type)| *seen_list == list && *seen_type == template_type)
+        .count();
+    seen.push((list, template_type.clone()));
+    (list, template_type, before)
+}
+
+/// Colours of the templates. Other template fields are scratch values of the add windows.
+fn template_colors(gui: &GuiSingletons) -> BTreeMap<TemplateKey, Color32> {
+    let mut seen = Vec::new();
+    let mut colors = BTreeMap::new();
+    for tool in gui.rotating_tools.iter().chain(gui.insert_tools.iter()) {
+        let key = template_key(&mut seen, "tool", tool.get_type());
+        colors.insert(key, tool.get_color());
+    }
+    for holder in gui.holders.iter() {
+        let key = template_key(&mut seen, "holder", holder.get_type());
+        colors.insert(key, holder.get_color());
+    }
+    for adapter in gui.adapters.iter() {
+        let key = template_key(&mut seen, "adapter", adapter.get_type());
+        colors.insert(key, adapter.get_color());
+    }
+    colors
+}
+
+impl Snapshot {
+    pub fn take(app: &ManagingApp) -> Self {
+        Self {
+            machines: app.machines.clone(),
+            tools: app.library.tools.clone(),
+            holders: app.library.holders.clone(),
+            adapters: app.library.adapters.clone(),
+            assemblies: app.library.assemblies.clone(),
+            colors: template_colors(&app.gui_singletons),
+        }
+    }
+
+    fn machines_match(&self, app: &ManagingApp) -> bool {
+        self.machines.len() == app.machines.len()
+            && self
+                .machines
+                .iter()
+                .zip(app.machines.iter())
+                .all(|(a, b)| a.same_setup(b))
+    }
+
+    fn library_matches(&self, app: &ManagingApp) -> bool {
+        self.tools == app.library.tools
+            && self.holders == app.library.holders
+            && self.adapters == app.library.adapters
+            && self.assemblies == app.library.assemblies
+    }
+
+    fn colors_match(&self, app: &ManagingApp) -> bool {
+        self.colors == template_colors(&app.gui_singletons)
+    }
+
+    /// Whether the app still holds this inventory. Which magazine is viewed does not count.
+    pub fn matches(&self, app: &ManagingApp) -> bool {
+        self.machines_match(app) && self.library_matches(app) && self.colors_match(app)
+    }
+
+    /// Describes what changed, for changes made outside of a named operation.
+    fn describe_change(&self, app: &ManagingApp) -> String {
+        if !self.colors_match(app) {
+            "Change colours".to_string()
+        } else if !self.machines_match(app) && !self.library_matches(app) {
+            "Edit machines and library".to_string()
+        } else if !self.machines_match(app) {
+            "Edit machines".to_string()
+        } else {
+            "Edit library".to_string()
+        }
+    }
+
+    /// Puts the inventory back. The viewed magazine of each machine is kept where possible.
+    fn restore(self, app: &mut ManagingApp) {
+        let mut machines = self.machines;
+        for (restored, current) in machines.iter_mut().zip(app.machines.iter()) {
+            if current
+                .current_magazine
+                .is_some_and(|index| index < restored.magazines.len())
+            {
+                restored.current_magazine = current.current_magazine;
+            }
+        }
+        app.machines = machines;
+        app.library.tools = self.tools;
+        app.library.holders = self.holders;
+        app.library.adapters = self.adapters;
+        app.library.assemblies = self.assemblies;
+        // Templates the snapshot does not know keep their colour
+        let gui = &mut app.gui_singletons;
+        let mut seen = Vec::new();
+        for tool in gui
+            .rotating_tools
+            .iter_mut()
+            .chain(gui.insert_tools.iter_mut())
+        {
+            let key = template_key(&mut seen, "tool", tool.get_type());
+            if let Some(color) = self.colors.get(&key) {
+                tool.set_color(*color);
+            }
+        }
+        for holder in gui.holders.iter_mut() {
+            let key = template_key(&mut seen, "holder", holder.get_type());
+            if let Some(color) = self.colors.get(&key) {
+                holder.set_color(*color);
+            }
+        }
+        for adapter in gui.adapters.iter_mut() {
+            let key = template_key(&mut seen, "adapter", adapter.get_type());
+            if let Some(color) = self.colors.get(&key) {
+                adapter.set_color(*color);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub label: String,
+    pub snapshot: Snapshot, // Inventory before the operation
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub undo: Vec<HistoryEntry>,
+    pub redo: Vec<HistoryEntry>,       // Last undone operation at the end
+    pub last: Option<Snapshot>,        // Inventory after the last recorded operation
+    pub pending_label: Option<String>, // Name of a change still being made
+}
+
+/// Name of the operation the app is in the middle of, if it changes the inventory.
+pub fn operation_label(app: &ManagingApp) -> Option<String> {
+    let selections = &app.move_selections;
+    let pocket = selections
+        .selected_slot_index_magazine
+        .or(selections.selected_tool_index_magazine)
+        .or(selections.selected_holder_index_magazine)
+        .or(selections.selected_adapter_index_magazine)
+        .or(selections.selected_assembly_index_magazine)
+        .or(selections.selected_comment_index_magazine);
+    let in_slot = |text: &str| match pocket {
+        Some(pocket) => format!("{} (slot {})", text, pocket),
+        None => text.to_string(),
+    };
+    if let Some(state) = &app.app_states.move_state {
+        return Some(match state {
+            MoveStates::ToolToMagazine => in_slot("Load tool"),
+            MoveStates::ToolToLibrary => in_slot("Remove tool"),
+            MoveStates::HolderToMagazine => in_slot("Load holder"),
+            MoveStates::HolderToLibrary => in_slot("Remove holder"),
+            MoveStates::AdapterToMagazine => in_slot("Load adapter"),
+            MoveStates::AdapterToLibrary => in_slot("Remove adapter"),
+            MoveStates::EditComment => in_slot("Edit comment"),
+            MoveStates::EditSlot => in_slot("Edit slot"),
+            MoveStates::AssemblyToMagazine => in_slot("Load assembly"),
+            MoveStates::AssemblyToLibrary => in_slot("Unload assembly"),
+            MoveStates::SwapSlots => in_slot("Swap slots"),
+            MoveStates::TransferSlot => in_slot("Move slot"),
+        });
+    }
+    let label = match app.app_states.app_state {
+        AppState::AddTool => "Add tool",
+        AppState::AddHolder => "Add holder",
+        AppState::AddAdapter => "Add adapter",
+        AppState::AddMachine => "Add machine",
+        AppState::Settings => "Change colours",
+        AppState::OffsetTransfer => "Import offsets",
+        AppState::BuildAssembly => "Build assembly",
+        AppState::MachineCapabilities => "Edit machine capabilities",
+        AppState::EditMachine => "Edit machine",
+        _ => return None,
+    };
+    Some(label.to_string())
+}
+
+/// Records a change of the inventory as one history entry. A change is only recorded once the
+/// user lets go of the mouse and leaves text fields, so dragging a value or typing a comment
+/// makes a single entry. `operation` is the label taken before the frame's moves completed.
+pub fn record_history(app: &mut ManagingApp, ctx: &egui::Context, operation: Option<String>) {
+    let Some(last) = &app.history.last else {
+        app.history.last = Some(Snapshot::take(app));
+        return;
+    };
+    if last.matches(app) {
+        app.history.pending_label = None;
+        return;
+    }
+    if app.history.pending_label.is_none() {
+        app.history.pending_label = Some(operation.unwrap_or_else(|| last.describe_change(app)));
+    }
+    let interacting =
+        ctx.input(|i| i.pointer.any_down()) || ctx.memory(|memory| memory.focused().is_some());
+    if !interacting {
+        commit(app);
+    }
+}
+
+fn commit(app: &mut ManagingApp) {
+    let snapshot = Snapshot::take(app);
+    let Some(before) = app.history.last.replace(snapshot) else {
+        return;
+    };
+    if before.matches(app) {
+        return;
+    }
+    let label = app
+        .history
+        .pending_label
+        .take()
+        .unwrap_or_else(|| before.describe_change(app));
+    app.history.undo.push(HistoryEntry {
+        label,
+        snapshot: before,
+    });
+    if app.history.undo.len() > MAX_ENTRIES {
+        app.history.undo.remove(0);
+    }
+    app.history.redo.clear();
+}
+
+/// Shows the restored inventory and drops moves that refer to the old one.
+fn after_restore(app: &mut ManagingApp) {
+    let history_open = matches!(app.app_states.app_state, AppState::History);
+    reset_states(app);
+    if history_open {
+        app.app_states.app_state = AppState::History;
+    }
+    app.gui_singletons.tool_filter = None;
+    app.gui_singletons.sort_by = SortBy::Slot;
+    if app
+        .selections
+        .machine
+        .is_some_and(|machine| machine >= app.machines.len())
+    {
+        app.selections.machine = None;
+    }
+    let magazine = app
+        .selections
+        .machine
+        .map(|machine| &app.machines[machine])
+        .and_then(|machine| {
+            machine
+                .current_magazine
+                .and_then(|index| machine.magazines.get(index))
+        });
+    app.display_magazine = match magazine {
+        Some(magazine) => magazine.clone(),
+        None => Magazine {
+            name: "None selected".to_string(),
+            contents: Vec::new(),
+            kind: MagazineKind::default(),
+        },
+    };
+    app.history.last = Some(Snapshot::take(app));
+    app.history.pending_label = None;
+}
+
+pub fn undo(app: &mut ManagingApp) {
+    commit(app);
+    let Some(entry) = app.history.undo.pop() else {
+        return;
+    };
+    let current = Snapshot::take(app);
+    entry.snapshot.restore(app);
+    app.history.redo.push(HistoryEntry {
+        label: entry.label,
+        snapshot: current,
+    });
+    after_restore(app);
+}
+
+pub fn redo(app: &mut ManagingApp) {
+    let Some(entry) = app.history.redo.pop() else {
+        return;
+    };
+    let current = Snapshot::take(app);
+    entry.snapshot.restore(app);
+    app.history.undo.push(HistoryEntry {
+        label: entry.label,
+        snapshot: current,
+    });
+    after_restore(app);
+}
+
+/// Ctrl+Z undoes, Ctrl+Shift+Z redoes. Text fields keep the keys for their own undo.
+pub fn history_shortcuts(app: &mut ManagingApp, ctx: &egui::Context) {
+    if ctx.memory(|memory| memory.focused().is_some()) {
+        return;
+    }
+    // Ctrl+Z also matches with shift held, so redo is checked first
+    if ctx.input_mut(|i| i.consume_shortcut(&REDO)) {
+        redo(app);
+    } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO)) {
+        undo(app);
+    }
+}
+
+pub fn history_panel(app: &mut ManagingApp, ctx: &egui::Context) {
+    let mut is_window_open = true;
+    let mut undo_steps = 0;
+    let mut redo_steps = 0;
+    egui::Window::new("History")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let history = &app.history;
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!history.undo.is_empty(), egui::Button::new("Undo"))
+                    .on_hover_text(ctx.format_shortcut(&UNDO))
+                    .clicked()
+                {
+                    undo_steps = 1;
+                }
+                if ui
+                    .add_enabled(!history.redo.is_empty(), egui::Button::new("Redo"))
+                    .on_hover_text(ctx.format_shortcut(&REDO))
+                    .clicked()
+                {
+                    redo_steps = 1;
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    if history.undo.is_empty() && history.redo.is_empty() {
+                        ui.label("Nothing done yet");
+                    }
+                    let count = history.undo.len();
+                    for (i, entry) in history.undo.iter().enumerate() {
+                        if ui
+                            .selectable_label(i + 1 == count, &entry.label)
+                            .on_hover_text("Undo back to here")
+                            .clicked()
+                        {
+                            undo_steps = count - i - 1;
+                        }
+                    }
+                    for (i, entry) in history.redo.iter().rev().enumerate() {
+                        if ui
+                            .selectable_label(
+                                false,
+                                egui::RichText::new(&entry.label).weak().italics(),
+                            )
+                            .on_hover_text("Redo up to here")
+                            .clicked()
+                        {
+                            redo_steps = i + 1;
+                        }
+                    }
+                });
+        });
+    for _ in 0..undo_steps {
+        undo(app);
+    }
+    for _ in 0..redo_steps {
+        redo(app);
+    }
+    if !is_window_open {
+        reset_states(app);
+    }
+}
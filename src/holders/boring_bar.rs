@@ -0,0 +1,165 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::external_holder::{Hand, InsertSeat};
+use crate::holder::HolderCategory;
+use crate::iso_insert::{ClearanceAngle, InsertShape};
+use egui::Color32;
+
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum BarMaterial {
+    #[default]
+    Steel,
+    Carbide,
+    Damped,
+}
+
+impl BarMaterial {
+    /// Overhang the bar can run without chatter, as a multiple of its shank diameter.
+    pub fn max_overhang_ratio(&self) -> f32 {
+        match self {
+            BarMaterial::Steel => 4.0,
+            BarMaterial::Carbide => 6.0,
+            BarMaterial::Damped => 10.0,
+        }
+    }
+}
+
+impl fmt::Display for BarMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BarMaterial::Steel => write!(f, "Steel"),
+            BarMaterial::Carbide => write!(f, "Carbide"),
+            BarMaterial::Damped => write!(f, "Damped"),
+        }
+    }
+}
+
+/// A boring bar for internal turning. Lengths in [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct BoringBar {
+    pub name: String,
+    pub shank_diameter: f32,
+    pub min_bore_diameter: f32,
+    pub overhang: f32, // Clamped stick-out
+    pub material: BarMaterial,
+    pub hand: Hand,
+    pub seat: InsertSeat,
+    pub color: Color32,
+}
+
+impl Default for BoringBar {
+    fn default() -> Self {
+        Self {
+            name: "S20S-PCLNR09".to_string(),
+            shank_diameter: 20.0,
+            min_bore_diameter: 25.0,
+            overhang: 60.0,
+            material: BarMaterial::Steel,
+            hand: Hand::Right,
+            seat: InsertSeat {
+                shape: InsertShape::C,
+                clearance: ClearanceAngle::N,
+                size: 9,
+            },
+            color: Color32::from_rgb(160, 120, 200),
+        }
+    }
+}
+
+impl BoringBar {
+    pub fn overhang_ratio(&self) -> f32 {
+        self.overhang / self.shank_diameter
+    }
+
+    pub fn is_overhang_too_long(&self) -> bool {
+        self.overhang_ratio() > self.material.max_overhang_ratio()
+    }
+
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.separator();
+        egui::Grid::new("boring_bar_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Shank diameter:");
+                ui.add(egui::DragValue::new(&mut self.shank_diameter).clamp_range(4.0..=100.0));
+                ui.end_row();
+                ui.label("Min bore diameter:");
+                ui.add(
+                    egui::DragValue::new(&mut self.min_bore_diameter)
+                        .clamp_range(self.shank_diameter..=200.0),
+                );
+                ui.end_row();
+                ui.label("Overhang:");
+                ui.add(egui::DragValue::new(&mut self.overhang).clamp_range(1.0..=1000.0));
+                ui.end_row();
+                ui.label("Material:");
+                ui.horizontal(|ui| {
+                    for material in BarMaterial::iter() {
+                        ui.radio_value(&mut self.material, material, material.to_string());
+                    }
+                });
+                ui.end_row();
+                ui.label("Hand:");
+                self.hand.edit(ui);
+                ui.end_row();
+                self.seat.edit(ui);
+            });
+        if self.is_overhang_too_long() {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!(
+                    "Overhang is {:.1}×D, a {} bar runs up to {}×D",
+                    self.overhang_ratio(),
+                    self.material.to_string().to_lowercase(),
+                    self.material.max_overhang_ratio()
+                ),
+            );
+        }
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.label(format!("min Ø{}", self.min_bore_diameter))
+                .on_hover_text("Minimum bore diameter");
+            let ratio = format!("{:.1}×D", self.overhang_ratio());
+            if self.is_overhang_too_long() {
+                ui.colored_label(Color32::YELLOW, ratio)
+            } else {
+                ui.label(ratio)
+            }
+            .on_hover_text(format!("Overhang {} mm", self.overhang));
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::TurningHolder
+    }
+
+    pub fn get_type(&self) -> String {
+        "Boring bar".to_string()
+    }
+}
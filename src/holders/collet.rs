@@ -1,18 +1,75 @@
+use std::fmt;
+
 use egui::Color32;
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::compatibility::*;
+use crate::holder::{display_mounting, HolderCategory};
+
+/// ER collet series of DIN 6499 / ISO 15488.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum ErSize {
+    Er11,
+    Er16,
+    Er20,
+    Er25,
+    #[default]
+    Er32,
+    Er40,
+}
+
+impl ErSize {
+    /// Smallest and largest shank diameter the collets of the series clamp [mm].
+    pub fn clamping_range(&self) -> (f32, f32) {
+        match self {
+            ErSize::Er11 => (0.5, 7.0),
+            ErSize::Er16 => (0.5, 10.0),
+            ErSize::Er20 => (1.0, 13.0),
+            ErSize::Er25 => (1.0, 16.0),
+            ErSize::Er32 => (2.0, 20.0),
+            ErSize::Er40 => (3.0, 26.0),
+        }
+    }
+}
 
-use crate::holder::HolderCategory;
+impl fmt::Display for ErSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErSize::Er11 => write!(f, "ER11"),
+            ErSize::Er16 => write!(f, "ER16"),
+            ErSize::Er20 => write!(f, "ER20"),
+            ErSize::Er25 => write!(f, "ER25"),
+            ErSize::Er32 => write!(f, "ER32"),
+            ErSize::Er40 => write!(f, "ER40"),
+        }
+    }
+}
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct Collet {
     pub name: String,
     pub color: Color32,
+    #[serde(default)]
+    pub clamping_range: Option<(f32, f32)>, // Shank diameters the collet can clamp
+    #[serde(default)]
+    pub taper: Option<ToolInterface>,
+    #[serde(default)]
+    pub er_size: Option<ErSize>,
+    #[serde(default)]
+    pub projection_length: Option<f32>, // Gauge line to chuck nose [mm]
 }
 
 impl Default for Collet {
     fn default() -> Self {
         Self {
-            name: "Collet".to_string(),
+            name: "ER32 collet chuck".to_string(),
             color: Color32::LIGHT_BLUE,
+            clamping_range: Some(ErSize::Er32.clamping_range()),
+            taper: None,
+            er_size: Some(ErSize::Er32),
+            projection_length: Some(70.0),
         }
     }
 }
@@ -24,15 +81,62 @@ impl Collet {
             ui.separator();
             ui.text_edit_singleline(&mut self.name);
         });
+        let er_size = self.er_size;
+        egui::ComboBox::from_label("Collet series")
+            .selected_text(
+                self.er_size
+                    .map(|size| size.to_string())
+                    .unwrap_or("Other".to_string()),
+            )
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.er_size, None, "Other");
+                for size in ErSize::iter() {
+                    ui.selectable_value(&mut self.er_size, Some(size), size.to_string());
+                }
+            });
+        if self.er_size != er_size {
+            if let Some(size) = self.er_size {
+                self.clamping_range = Some(size.clamping_range());
+            }
+        }
+        ui.horizontal(|ui| {
+            let mut known = self.clamping_range.is_some();
+            ui.checkbox(&mut known, "Clamping range Ø");
+            match (known, &mut self.clamping_range) {
+                (true, Some((min, max))) => {
+                    ui.add(egui::DragValue::new(min).clamp_range(0.0..=*max));
+                    ui.label("-");
+                    ui.add(egui::DragValue::new(max).clamp_range(*min..=200.0));
+                }
+                (true, None) => self.clamping_range = Some((2.0, 20.0)),
+                (false, _) => self.clamping_range = None,
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut known = self.projection_length.is_some();
+            ui.checkbox(&mut known, "Projection length")
+                .on_hover_text("Gauge line to chuck nose");
+            match (known, &mut self.projection_length) {
+                (true, Some(length)) => {
+                    ui.add(egui::DragValue::new(length).clamp_range(1.0..=500.0));
+                }
+                (true, None) => self.projection_length = Some(70.0),
+                (false, _) => self.projection_length = None,
+            }
+        });
+        interface_combo(ui, "Taper", &mut self.taper);
         ui.separator();
         *add = ui.button("Add").clicked()
     }
 
     pub fn display(&self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            ui.horizontal(|ui| {
-                ui.colored_label(self.color, &self.name);
-            });
+            ui.colored_label(self.color, &self.name);
+            if let Some((min, max)) = self.clamping_range {
+                ui.label(format!("Ø{}-{}", min, max))
+                    .on_hover_text("Clamping range");
+            }
+            display_mounting(ui, self.taper, self.projection_length);
         });
     }
 
@@ -52,6 +156,18 @@ impl Collet {
         HolderCategory::MillingHolder
     }
 
+    pub fn get_clamping_range(&self) -> Option<(f32, f32)> {
+        self.clamping_range
+    }
+
+    pub fn get_taper(&self) -> Option<ToolInterface> {
+        self.taper
+    }
+
+    pub fn get_projection_length(&self) -> Option<f32> {
+        self.projection_length
+    }
+
     pub fn get_type(&self) -> String {
         "Collet".to_string()
     }
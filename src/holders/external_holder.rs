@@ -0,0 +1,442 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::holder::HolderCategory;
+use crate::iso_insert::{ClearanceAngle, InsertShape};
+use egui::Color32;
+
+/// Cutting direction of a lathe tool, last letter of the holder code.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum Hand {
+    #[default]
+    Right,
+    Left,
+    Neutral,
+}
+
+impl Hand {
+    pub fn letter(&self) -> char {
+        match self {
+            Hand::Right => 'R',
+            Hand::Left => 'L',
+            Hand::Neutral => 'N',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        Hand::iter().find(|hand| hand.letter() == letter)
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for hand in Hand::iter() {
+                ui.radio_value(self, hand, hand.to_string());
+            }
+        });
+    }
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hand::Right => write!(f, "Right"),
+            Hand::Left => write!(f, "Left"),
+            Hand::Neutral => write!(f, "Neutral"),
+        }
+    }
+}
+
+/// Insert a turning holder or boring bar is made for.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct InsertSeat {
+    pub shape: InsertShape,
+    pub clearance: ClearanceAngle,
+    pub size: u32, // Cutting edge length of the insert [mm], as in both codes
+}
+
+impl InsertSeat {
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.label("Insert shape:");
+        egui::ComboBox::from_id_source("seat_shape")
+            .selected_text(self.shape.to_string())
+            .show_ui(ui, |ui| {
+                for shape in InsertShape::iter() {
+                    ui.selectable_value(&mut self.shape, shape, shape.to_string());
+                }
+            });
+        ui.end_row();
+        ui.label("Insert clearance:");
+        egui::ComboBox::from_id_source("seat_clearance")
+            .selected_text(self.clearance.to_string())
+            .show_ui(ui, |ui| {
+                for clearance in ClearanceAngle::iter() {
+                    ui.selectable_value(&mut self.clearance, clearance, clearance.to_string());
+                }
+            });
+        ui.end_row();
+        ui.label("Insert size:");
+        ui.add(egui::DragValue::new(&mut self.size).clamp_range(1..=99));
+        ui.end_row();
+    }
+}
+
+/// Clamping systems, first letter of the holder code.
+pub const CLAMPING_SYSTEMS: [(char, &str); 5] = [
+    ('C', "Top clamp"),
+    ('D', "Rigid clamp"),
+    ('M', "Top clamp and pin"),
+    ('P', "Lever"),
+    ('S', "Screw"),
+];
+
+/// Holder styles, third letter of the holder code, with their lead angle [°].
+pub const HOLDER_STYLES: [(char, f32); 22] = [
+    ('A', 90.0),
+    ('B', 75.0),
+    ('C', 90.0),
+    ('D', 45.0),
+    ('E', 60.0),
+    ('F', 90.0),
+    ('G', 90.0),
+    ('H', 107.5),
+    ('J', 93.0),
+    ('K', 75.0),
+    ('L', 95.0),
+    ('M', 50.0),
+    ('N', 63.0),
+    ('P', 117.5),
+    ('Q', 107.5),
+    ('R', 75.0),
+    ('S', 45.0),
+    ('T', 60.0),
+    ('U', 93.0),
+    ('V', 72.5),
+    ('W', 60.0),
+    ('Y', 85.0),
+];
+
+/// Holder length codes and the length they stand for [mm].
+pub const HOLDER_LENGTHS: [(char, u32); 22] = [
+    ('A', 32),
+    ('B', 40),
+    ('C', 50),
+    ('D', 60),
+    ('E', 70),
+    ('F', 80),
+    ('G', 90),
+    ('H', 100),
+    ('J', 110),
+    ('K', 125),
+    ('L', 140),
+    ('M', 150),
+    ('N', 160),
+    ('P', 170),
+    ('Q', 180),
+    ('R', 200),
+    ('S', 250),
+    ('T', 300),
+    ('U', 350),
+    ('V', 400),
+    ('W', 450),
+    ('Y', 500),
+];
+
+/// An external turning holder described by its ISO 5608 code, e.g. PCLNR 2525M12.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ExternalHolder {
+    pub name: String,
+    pub clamping: char,
+    pub style: char,
+    pub hand: Hand,
+    pub seat: InsertSeat,
+    pub shank_height: u32, // [mm]
+    pub shank_width: u32,  // [mm]
+    pub length: char,      // Length code, see `HOLDER_LENGTHS`
+    pub color: Color32,
+    #[serde(skip)]
+    pub code_input: String,
+    #[serde(skip)]
+    pub code_error: Option<String>,
+}
+
+impl Default for ExternalHolder {
+    fn default() -> Self {
+        Self {
+            name: "PCLNR 2525M12".to_string(),
+            clamping: 'P',
+            style: 'L',
+            hand: Hand::Right,
+            seat: InsertSeat {
+                shape: InsertShape::C,
+                clearance: ClearanceAngle::N,
+                size: 12,
+            },
+            shank_height: 25,
+            shank_width: 25,
+            length: 'M',
+            color: Color32::from_rgb(200, 160, 60),
+            code_input: String::new(),
+            code_error: None,
+        }
+    }
+}
+
+impl ExternalHolder {
+    /// Parses an ISO 5608 code such as `PCLNR 2525M12`. Spaces are ignored.
+    pub fn from_code(code: &str) -> Result<ExternalHolder, String> {
+        let chars: Vec<char> = code
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if chars.len() != 12 {
+            return Err(format!(
+                "{} should be five letters, shank height and width, length letter and insert size",
+                code.trim()
+            ));
+        }
+        let clamping = chars[0];
+        if !CLAMPING_SYSTEMS
+            .iter()
+            .any(|(letter, _)| *letter == clamping)
+        {
+            return Err(format!("Unknown clamping system {}", clamping));
+        }
+        let shape = InsertShape::iter()
+            .find(|shape| shape.letter() == chars[1])
+            .ok_or(format!("Unknown insert shape {}", chars[1]))?;
+        let style = chars[2];
+        if !HOLDER_STYLES.iter().any(|(letter, _)| *letter == style) {
+            return Err(format!("Unknown holder style {}", style));
+        }
+        let clearance = ClearanceAngle::iter()
+            .find(|clearance| clearance.letter() == chars[3])
+            .ok_or(format!("Unknown insert clearance {}", chars[3]))?;
+        let hand = Hand::from_letter(chars[4]).ok_or(format!("Unknown hand {}", chars[4]))?;
+        let number = |start: usize, what: &str| {
+            let digits: String = chars[start..start + 2].iter().collect();
+            digits
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or(format!("{} {} is not a size", what, digits))
+        };
+        let shank_height = number(5, "Shank height")?;
+        let shank_width = number(7, "Shank width")?;
+        let length = chars[9];
+        if !HOLDER_LENGTHS.iter().any(|(letter, _)| *letter == length) {
+            return Err(format!("Unknown holder length {}", length));
+        }
+        let size = number(10, "Insert size")?;
+        let mut holder = ExternalHolder {
+            clamping,
+            style,
+            hand,
+            seat: InsertSeat {
+                shape,
+                clearance,
+                size,
+            },
+            shank_height,
+            shank_width,
+            length,
+            ..ExternalHolder::default()
+        };
+        holder.name = holder.code();
+        Ok(holder)
+    }
+
+    pub fn code(&self) -> String {
+        format!(
+            "{}{}{}{}{} {:02}{:02}{}{:02}",
+            self.clamping,
+            self.seat.shape.letter(),
+            self.style,
+            self.seat.clearance.letter(),
+            self.hand.letter(),
+            self.shank_height,
+            self.shank_width,
+            self.length,
+            self.seat.size
+        )
+    }
+
+    /// Lead angle of the holder style [°].
+    pub fn lead_angle(&self) -> Option<f32> {
+        HOLDER_STYLES
+            .iter()
+            .find(|(letter, _)| *letter == self.style)
+            .map(|(_, angle)| *angle)
+    }
+
+    pub fn length_mm(&self) -> Option<u32> {
+        HOLDER_LENGTHS
+            .iter()
+            .find(|(letter, _)| *letter == self.length)
+            .map(|(_, length)| *length)
+    }
+
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        let code_before = self.code();
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("ISO code");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.code_input)
+                .on_hover_text("For example PCLNR 2525M12");
+            if ui.button("Apply").clicked() {
+                match ExternalHolder::from_code(&self.code_input) {
+                    Ok(parsed) => {
+                        *self = ExternalHolder {
+                            color: self.color,
+                            ..parsed
+                        }
+                    }
+                    Err(error) => self.code_error = Some(error),
+                }
+            }
+        });
+        if let Some(error) = &self.code_error {
+            ui.colored_label(Color32::RED, error);
+        }
+        ui.separator();
+        egui::Grid::new("external_holder_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Clamping:");
+                egui::ComboBox::from_id_source("holder_clamping")
+                    .selected_text(self.clamping.to_string())
+                    .show_ui(ui, |ui| {
+                        for (letter, name) in CLAMPING_SYSTEMS {
+                            ui.selectable_value(
+                                &mut self.clamping,
+                                letter,
+                                format!("{} {}", letter, name),
+                            );
+                        }
+                    });
+                ui.end_row();
+                ui.label("Style:");
+                egui::ComboBox::from_id_source("holder_style")
+                    .selected_text(self.style.to_string())
+                    .show_ui(ui, |ui| {
+                        for (letter, angle) in HOLDER_STYLES {
+                            ui.selectable_value(
+                                &mut self.style,
+                                letter,
+                                format!("{} ({}°)", letter, angle),
+                            );
+                        }
+                    });
+                ui.end_row();
+                ui.label("Hand:");
+                self.hand.edit(ui);
+                ui.end_row();
+                self.seat.edit(ui);
+                ui.label("Shank height × width:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.shank_height).clamp_range(6..=64));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(&mut self.shank_width).clamp_range(6..=64));
+                });
+                ui.end_row();
+                ui.label("Length:");
+                egui::ComboBox::from_id_source("holder_length")
+                    .selected_text(self.length.to_string())
+                    .show_ui(ui, |ui| {
+                        for (letter, length) in HOLDER_LENGTHS {
+                            ui.selectable_value(
+                                &mut self.length,
+                                letter,
+                                format!("{} ({} mm)", letter, length),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+        // A name left at the code follows the code
+        if self.name == code_before {
+            self.name = self.code();
+        }
+        ui.label(format!("Code: {}", self.code()));
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            if self.name != self.code() {
+                ui.label(self.code());
+            }
+            ui.label(format!("{}°", self.lead_angle().unwrap_or_default()))
+                .on_hover_text("Lead angle");
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::TurningHolder
+    }
+
+    pub fn get_type(&self) -> String {
+        "External turning holder".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holder::Holder;
+
+    #[test]
+    fn parses_pclnr_2525m12() {
+        let holder = ExternalHolder::from_code("pclnr2525m12").unwrap();
+        assert_eq!((holder.clamping, holder.style), ('P', 'L'));
+        assert_eq!(holder.hand, Hand::Right);
+        assert_eq!(
+            holder.seat,
+            InsertSeat {
+                shape: InsertShape::C,
+                clearance: ClearanceAngle::N,
+                size: 12,
+            }
+        );
+        assert_eq!((holder.shank_height, holder.shank_width), (25, 25));
+        assert_eq!(holder.length, 'M');
+        assert_eq!(holder.lead_angle(), Some(95.0));
+        assert_eq!(holder.length_mm(), Some(150));
+        assert_eq!(
+            Holder::ExternalHolder(holder.clone()).get_projection_length(),
+            Some(150.0)
+        );
+        assert_eq!(holder.code(), "PCLNR 2525M12");
+        assert_eq!(ExternalHolder::from_code(&holder.code()), Ok(holder));
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        assert!(ExternalHolder::from_code("PCLNR 2525M").is_err());
+        assert!(ExternalHolder::from_code("PCLNX 2525M12").is_err());
+        assert!(ExternalHolder::from_code("PCLNR 0025M12").is_err());
+        assert!(ExternalHolder::from_code("PCLNR 2525?12").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::external_holder::Hand;
+use crate::holder::HolderCategory;
+use egui::Color32;
+
+/// A grooving or parting blade. Lengths in [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct GroovingBlade {
+    pub name: String,
+    pub width: f32,     // Insert width the blade takes
+    pub max_depth: f32, // Deepest groove, or the largest radius it can part off
+    pub parting: bool,
+    pub hand: Hand,
+    pub color: Color32,
+}
+
+impl Default for GroovingBlade {
+    fn default() -> Self {
+        Self {
+            name: "Parting blade 3 mm".to_string(),
+            width: 3.0,
+            max_depth: 20.0,
+            parting: true,
+            hand: Hand::Neutral,
+            color: Color32::from_rgb(120, 180, 120),
+        }
+    }
+}
+
+impl GroovingBlade {
+    /// Largest bar diameter the blade can part off.
+    pub fn max_parting_diameter(&self) -> f32 {
+        self.max_depth * 2.0
+    }
+
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.separator();
+        egui::Grid::new("grooving_blade_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Width:");
+                ui.add(
+                    egui::DragValue::new(&mut self.width)
+                        .speed(0.1)
+                        .clamp_range(0.5..=20.0),
+                );
+                ui.end_row();
+                ui.label("Max depth:");
+                ui.add(egui::DragValue::new(&mut self.max_depth).clamp_range(0.5..=100.0));
+                ui.end_row();
+                ui.label("Parting:");
+                ui.checkbox(&mut self.parting, "")
+                    .on_hover_text("Blade can part off bars up to twice its depth");
+                ui.end_row();
+                ui.label("Hand:");
+                self.hand.edit(ui);
+                ui.end_row();
+            });
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.label(format!("{} mm", self.width))
+                .on_hover_text("Width");
+            if self.parting {
+                ui.label(format!("Ø{}", self.max_parting_diameter()))
+                    .on_hover_text("Largest parting diameter");
+            } else {
+                ui.label(format!("{} mm deep", self.max_depth));
+            }
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::TurningHolder
+    }
+
+    pub fn get_type(&self) -> String {
+        "Grooving/parting blade".to_string()
+    }
+}
@@ -1,8 +1,17 @@
 use egui::Color32;
 
 use crate::app::*;
+use crate::boring_bar::*;
 use crate::collet::*;
+use crate::compatibility::ToolInterface;
+use crate::external_holder::*;
+use crate::grooving_blade::*;
+use crate::hydraulic_chuck::*;
+use crate::milling_arbor::*;
 use crate::resources::*;
+use crate::shrink_fit::*;
+use crate::threading_holder::*;
+use crate::weldon::*;
 use crate::ManagingApp;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
@@ -18,50 +27,171 @@ pub enum HolderCategory {
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub enum Holder {
     Collet(Collet),
+    ShrinkFit(ShrinkFit),
+    Weldon(Weldon),
+    HydraulicChuck(HydraulicChuck),
+    MillingArbor(MillingArbor),
+    ExternalHolder(ExternalHolder),
+    BoringBar(BoringBar),
+    GroovingBlade(GroovingBlade),
+    ThreadingHolder(ThreadingHolder),
 }
 
 impl Holder {
     pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
         match self {
             Holder::Collet(collet) => collet.holder_edit(ui, add),
+            Holder::ShrinkFit(holder) => holder.holder_edit(ui, add),
+            Holder::Weldon(holder) => holder.holder_edit(ui, add),
+            Holder::HydraulicChuck(holder) => holder.holder_edit(ui, add),
+            Holder::MillingArbor(holder) => holder.holder_edit(ui, add),
+            Holder::ExternalHolder(holder) => holder.holder_edit(ui, add),
+            Holder::BoringBar(holder) => holder.holder_edit(ui, add),
+            Holder::GroovingBlade(holder) => holder.holder_edit(ui, add),
+            Holder::ThreadingHolder(holder) => holder.holder_edit(ui, add),
         }
     }
 
     pub fn display(&self, ui: &mut egui::Ui) {
         match self {
             Holder::Collet(collet) => collet.display(ui),
+            Holder::ShrinkFit(holder) => holder.display(ui),
+            Holder::Weldon(holder) => holder.display(ui),
+            Holder::HydraulicChuck(holder) => holder.display(ui),
+            Holder::MillingArbor(holder) => holder.display(ui),
+            Holder::ExternalHolder(holder) => holder.display(ui),
+            Holder::BoringBar(holder) => holder.display(ui),
+            Holder::GroovingBlade(holder) => holder.display(ui),
+            Holder::ThreadingHolder(holder) => holder.display(ui),
         }
     }
 
     pub fn get_name(&self) -> String {
         match self {
-            Holder::Collet(collet) => collet.name.clone(),
+            Holder::Collet(collet) => collet.get_name(),
+            Holder::ShrinkFit(holder) => holder.get_name(),
+            Holder::Weldon(holder) => holder.get_name(),
+            Holder::HydraulicChuck(holder) => holder.get_name(),
+            Holder::MillingArbor(holder) => holder.get_name(),
+            Holder::ExternalHolder(holder) => holder.get_name(),
+            Holder::BoringBar(holder) => holder.get_name(),
+            Holder::GroovingBlade(holder) => holder.get_name(),
+            Holder::ThreadingHolder(holder) => holder.get_name(),
         }
     }
 
     pub fn set_color(&mut self, color: Color32) {
         match self {
             Holder::Collet(collet) => collet.set_color(color),
+            Holder::ShrinkFit(holder) => holder.set_color(color),
+            Holder::Weldon(holder) => holder.set_color(color),
+            Holder::HydraulicChuck(holder) => holder.set_color(color),
+            Holder::MillingArbor(holder) => holder.set_color(color),
+            Holder::ExternalHolder(holder) => holder.set_color(color),
+            Holder::BoringBar(holder) => holder.set_color(color),
+            Holder::GroovingBlade(holder) => holder.set_color(color),
+            Holder::ThreadingHolder(holder) => holder.set_color(color),
         }
     }
 
     pub fn get_color(&self) -> Color32 {
         match self {
-            Holder::Collet(collet) => collet.color,
+            Holder::Collet(collet) => collet.get_color(),
+            Holder::ShrinkFit(holder) => holder.get_color(),
+            Holder::Weldon(holder) => holder.get_color(),
+            Holder::HydraulicChuck(holder) => holder.get_color(),
+            Holder::MillingArbor(holder) => holder.get_color(),
+            Holder::ExternalHolder(holder) => holder.get_color(),
+            Holder::BoringBar(holder) => holder.get_color(),
+            Holder::GroovingBlade(holder) => holder.get_color(),
+            Holder::ThreadingHolder(holder) => holder.get_color(),
         }
     }
 
     pub fn get_category(&self) -> HolderCategory {
         match self {
-            Holder::Collet(_) => HolderCategory::MillingHolder,
+            Holder::Collet(collet) => collet.get_category(),
+            Holder::ShrinkFit(holder) => holder.get_category(),
+            Holder::Weldon(holder) => holder.get_category(),
+            Holder::HydraulicChuck(holder) => holder.get_category(),
+            Holder::MillingArbor(holder) => holder.get_category(),
+            Holder::ExternalHolder(holder) => holder.get_category(),
+            Holder::BoringBar(holder) => holder.get_category(),
+            Holder::GroovingBlade(holder) => holder.get_category(),
+            Holder::ThreadingHolder(holder) => holder.get_category(),
         }
     }
 
     pub fn get_type(&self) -> String {
         match self {
             Holder::Collet(collet) => collet.get_type(),
+            Holder::ShrinkFit(holder) => holder.get_type(),
+            Holder::Weldon(holder) => holder.get_type(),
+            Holder::HydraulicChuck(holder) => holder.get_type(),
+            Holder::MillingArbor(holder) => holder.get_type(),
+            Holder::ExternalHolder(holder) => holder.get_type(),
+            Holder::BoringBar(holder) => holder.get_type(),
+            Holder::GroovingBlade(holder) => holder.get_type(),
+            Holder::ThreadingHolder(holder) => holder.get_type(),
         }
     }
+
+    pub fn get_clamping_range(&self) -> Option<(f32, f32)> {
+        match self {
+            Holder::Collet(collet) => collet.get_clamping_range(),
+            Holder::ShrinkFit(holder) => holder.get_clamping_range(),
+            Holder::Weldon(holder) => holder.get_clamping_range(),
+            Holder::HydraulicChuck(holder) => holder.get_clamping_range(),
+            // Arbors carry bored cutters and turning holders take inserts, neither a shank
+            _ => None,
+        }
+    }
+
+    /// Insert seat of holders that take ISO inserts.
+    pub fn get_insert_seat(&self) -> Option<InsertSeat> {
+        match self {
+            Holder::ExternalHolder(holder) => Some(holder.seat),
+            Holder::BoringBar(bar) => Some(bar.seat),
+            _ => None,
+        }
+    }
+
+    pub fn get_taper(&self) -> Option<ToolInterface> {
+        match self {
+            Holder::Collet(collet) => collet.get_taper(),
+            Holder::ShrinkFit(holder) => holder.get_taper(),
+            Holder::Weldon(holder) => holder.get_taper(),
+            Holder::HydraulicChuck(holder) => holder.get_taper(),
+            Holder::MillingArbor(holder) => holder.get_taper(),
+            // Turning holders sit in a tool block, not a collet or a spindle taper
+            _ => None,
+        }
+    }
+
+    /// Gauge line to holder nose, the holder length of an assembly.
+    pub fn get_projection_length(&self) -> Option<f32> {
+        match self {
+            Holder::Collet(collet) => collet.get_projection_length(),
+            Holder::ShrinkFit(holder) => holder.get_projection_length(),
+            Holder::Weldon(holder) => holder.get_projection_length(),
+            Holder::HydraulicChuck(holder) => holder.get_projection_length(),
+            Holder::MillingArbor(holder) => holder.get_projection_length(),
+            // The ISO 5608 length letter stands for the overall shank length
+            Holder::ExternalHolder(holder) => holder.length_mm().map(|length| length as f32),
+            _ => None,
+        }
+    }
+}
+
+/// Taper and projection length of a milling holder, as shown in the holder column.
+pub fn display_mounting(ui: &mut egui::Ui, taper: Option<ToolInterface>, projection: Option<f32>) {
+    if let Some(taper) = taper {
+        ui.label(taper.to_string());
+    }
+    if let Some(projection) = projection {
+        ui.label(format!("L{}", projection))
+            .on_hover_text("Projection length");
+    }
 }
 
 pub fn modify_holder(holder: &mut Holder, ui: &mut egui::Ui, add: &mut bool) {
@@ -99,7 +229,7 @@ pub fn add_standard_holder(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_
         .holders
         .get(app.selections.selected_holder_index)
     {
-        Some(Holder::Collet(collet)) => collet.name.clone(),
+        Some(holder) => holder.get_name(),
         None => String::from("Select holder"),
     };
     egui::ComboBox::from_label("Select type of holder")
@@ -107,11 +237,8 @@ pub fn add_standard_holder(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_
         .show_ui(ui, |ui| {
             // get index to selected holder
             for (i, holder) in app.gui_singletons.holders.iter().enumerate() {
-                let label = match holder {
-                    Holder::Collet(collet) => collet.get_type(),
-                };
                 if ui
-                    .selectable_label(app.selections.selected_holder_index == i, label)
+                    .selectable_label(app.selections.selected_holder_index == i, holder.get_type())
                     .clicked()
                 {
                     app.selections.selected_holder_index = i;
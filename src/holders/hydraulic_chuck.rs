@@ -0,0 +1,114 @@
+use egui::Color32;
+
+use crate::compatibility::*;
+use crate::holder::{display_mounting, HolderCategory};
+
+/// A hydraulic expansion chuck, optionally fitted with a reduction sleeve. Lengths in [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct HydraulicChuck {
+    pub name: String,
+    pub color: Color32,
+    pub bore_diameter: f32,
+    pub sleeve_bore: Option<f32>, // Bore of the fitted reduction sleeve
+    pub projection_length: f32,   // Gauge line to chuck nose
+    pub taper: Option<ToolInterface>,
+}
+
+impl Default for HydraulicChuck {
+    fn default() -> Self {
+        Self {
+            name: "Hydraulic chuck Ø20".to_string(),
+            color: Color32::from_rgb(60, 200, 200),
+            bore_diameter: 20.0,
+            sleeve_bore: None,
+            projection_length: 80.0,
+            taper: None,
+        }
+    }
+}
+
+impl HydraulicChuck {
+    /// Diameter of the shank the chuck currently clamps.
+    pub fn clamping_diameter(&self) -> f32 {
+        self.sleeve_bore.unwrap_or(self.bore_diameter)
+    }
+
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Bore Ø");
+            ui.add(egui::DragValue::new(&mut self.bore_diameter).clamp_range(6.0..=32.0));
+        });
+        ui.horizontal(|ui| {
+            let mut sleeve = self.sleeve_bore.is_some();
+            ui.checkbox(&mut sleeve, "Reduction sleeve Ø");
+            match (sleeve, &mut self.sleeve_bore) {
+                (true, Some(bore)) => {
+                    ui.add(egui::DragValue::new(bore).clamp_range(2.0..=self.bore_diameter));
+                }
+                (true, None) => self.sleeve_bore = Some(self.bore_diameter / 2.0),
+                (false, _) => self.sleeve_bore = None,
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Projection length")
+                .on_hover_text("Gauge line to chuck nose");
+            ui.add(egui::DragValue::new(&mut self.projection_length).clamp_range(1.0..=500.0));
+        });
+        interface_combo(ui, "Taper", &mut self.taper);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            match self.sleeve_bore {
+                Some(sleeve) => ui
+                    .label(format!("Ø{}/{}", self.bore_diameter, sleeve))
+                    .on_hover_text("Bore / sleeve bore"),
+                None => ui
+                    .label(format!("Ø{}", self.bore_diameter))
+                    .on_hover_text("Bore"),
+            };
+            display_mounting(ui, self.taper, Some(self.projection_length));
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::MillingHolder
+    }
+
+    pub fn get_clamping_range(&self) -> Option<(f32, f32)> {
+        let diameter = self.clamping_diameter();
+        Some((diameter, diameter))
+    }
+
+    pub fn get_taper(&self) -> Option<ToolInterface> {
+        self.taper
+    }
+
+    pub fn get_projection_length(&self) -> Option<f32> {
+        Some(self.projection_length)
+    }
+
+    pub fn get_type(&self) -> String {
+        "Hydraulic chuck".to_string()
+    }
+}
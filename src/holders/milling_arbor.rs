@@ -0,0 +1,85 @@
+use egui::Color32;
+
+use crate::compatibility::*;
+use crate::holder::{display_mounting, HolderCategory};
+
+/// A shell mill arbor carrying face and shoulder mills on its pilot. Lengths in [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct MillingArbor {
+    pub name: String,
+    pub color: Color32,
+    pub arbor_diameter: f32, // Pilot diameter, the bore of the cutters it takes
+    pub projection_length: f32, // Gauge line to the cutter seat
+    pub taper: Option<ToolInterface>,
+}
+
+impl Default for MillingArbor {
+    fn default() -> Self {
+        Self {
+            name: "Shell mill arbor Ø22".to_string(),
+            color: Color32::from_rgb(200, 200, 120),
+            arbor_diameter: 22.0,
+            projection_length: 50.0,
+            taper: None,
+        }
+    }
+}
+
+impl MillingArbor {
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Arbor Ø");
+            ui.add(egui::DragValue::new(&mut self.arbor_diameter).clamp_range(8.0..=100.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Projection length")
+                .on_hover_text("Gauge line to the cutter seat");
+            ui.add(egui::DragValue::new(&mut self.projection_length).clamp_range(1.0..=500.0));
+        });
+        interface_combo(ui, "Taper", &mut self.taper);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.label(format!("Ø{}", self.arbor_diameter))
+                .on_hover_text("Arbor");
+            display_mounting(ui, self.taper, Some(self.projection_length));
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::MillingHolder
+    }
+
+    pub fn get_taper(&self) -> Option<ToolInterface> {
+        self.taper
+    }
+
+    pub fn get_projection_length(&self) -> Option<f32> {
+        Some(self.projection_length)
+    }
+
+    pub fn get_type(&self) -> String {
+        "Milling arbor".to_string()
+    }
+}
@@ -1,2 +1,10 @@
+pub mod boring_bar;
 pub mod collet;
+pub mod external_holder;
+pub mod grooving_blade;
 pub mod holder;
+pub mod hydraulic_chuck;
+pub mod milling_arbor;
+pub mod shrink_fit;
+pub mod threading_holder;
+pub mod weldon;
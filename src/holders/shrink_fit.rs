@@ -0,0 +1,89 @@
+use egui::Color32;
+
+use crate::compatibility::*;
+use crate::holder::{display_mounting, HolderCategory};
+
+/// A shrink-fit chuck, clamping an h6 shank of exactly its bore diameter. Lengths in [mm].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ShrinkFit {
+    pub name: String,
+    pub color: Color32,
+    pub bore_diameter: f32,
+    pub gauge_length: f32, // Gauge line to chuck nose
+    pub taper: Option<ToolInterface>,
+}
+
+impl Default for ShrinkFit {
+    fn default() -> Self {
+        Self {
+            name: "Shrink-fit Ø12".to_string(),
+            color: Color32::from_rgb(255, 140, 60),
+            bore_diameter: 12.0,
+            gauge_length: 80.0,
+            taper: None,
+        }
+    }
+}
+
+impl ShrinkFit {
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Bore Ø");
+            ui.add(egui::DragValue::new(&mut self.bore_diameter).clamp_range(3.0..=32.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Gauge length")
+                .on_hover_text("Gauge line to chuck nose");
+            ui.add(egui::DragValue::new(&mut self.gauge_length).clamp_range(1.0..=500.0));
+        });
+        interface_combo(ui, "Taper", &mut self.taper);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.label(format!("Ø{}", self.bore_diameter))
+                .on_hover_text("Bore");
+            display_mounting(ui, self.taper, Some(self.gauge_length));
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::MillingHolder
+    }
+
+    pub fn get_clamping_range(&self) -> Option<(f32, f32)> {
+        Some((self.bore_diameter, self.bore_diameter))
+    }
+
+    pub fn get_taper(&self) -> Option<ToolInterface> {
+        self.taper
+    }
+
+    pub fn get_projection_length(&self) -> Option<f32> {
+        Some(self.gauge_length)
+    }
+
+    pub fn get_type(&self) -> String {
+        "Shrink-fit".to_string()
+    }
+}
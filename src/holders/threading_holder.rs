@@ -0,0 +1,116 @@
+use crate::external_holder::Hand;
+use crate::holder::HolderCategory;
+use egui::Color32;
+
+/// A laydown threading holder, external or internal.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ThreadingHolder {
+    pub name: String,
+    pub internal: bool,
+    pub hand: Hand,
+    pub insert_size: u32,        // Insert size, e.g. 16 for 16ER inserts
+    pub pitch_range: (f32, f32), // Pitches the inserts for this holder cover [mm]
+    pub min_bore_diameter: f32,  // Only used by internal holders [mm]
+    pub color: Color32,
+}
+
+impl Default for ThreadingHolder {
+    fn default() -> Self {
+        Self {
+            name: "SER 2525M16".to_string(),
+            internal: false,
+            hand: Hand::Right,
+            insert_size: 16,
+            pitch_range: (0.5, 3.0),
+            min_bore_diameter: 20.0,
+            color: Color32::from_rgb(220, 120, 120),
+        }
+    }
+}
+
+impl ThreadingHolder {
+    pub fn covers_pitch(&self, pitch: f32) -> bool {
+        let (min, max) = self.pitch_range;
+        (min..=max).contains(&pitch)
+    }
+
+    pub fn holder_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.separator();
+        egui::Grid::new("threading_holder_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Internal:");
+                ui.checkbox(&mut self.internal, "");
+                ui.end_row();
+                ui.label("Hand:");
+                self.hand.edit(ui);
+                ui.end_row();
+                ui.label("Insert size:");
+                ui.add(egui::DragValue::new(&mut self.insert_size).clamp_range(6..=27));
+                ui.end_row();
+                ui.label("Pitch range:");
+                ui.horizontal(|ui| {
+                    let (min, max) = &mut self.pitch_range;
+                    ui.add(
+                        egui::DragValue::new(min)
+                            .speed(0.05)
+                            .clamp_range(0.2..=*max),
+                    );
+                    ui.label("-");
+                    ui.add(
+                        egui::DragValue::new(max)
+                            .speed(0.05)
+                            .clamp_range(*min..=8.0),
+                    );
+                });
+                ui.end_row();
+                if self.internal {
+                    ui.label("Min bore diameter:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.min_bore_diameter).clamp_range(4.0..=200.0),
+                    );
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            let (min, max) = self.pitch_range;
+            ui.label(format!("P{}-{}", min, max))
+                .on_hover_text("Pitch range");
+            if self.internal {
+                ui.label(format!("min Ø{}", self.min_bore_diameter))
+                    .on_hover_text("Minimum bore diameter");
+            }
+        });
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_category(&self) -> HolderCategory {
+        HolderCategory::TurningHolder
+    }
+
+    pub fn get_type(&self) -> String {
+        "Threading holder".to_string()
+    }
+}
@@ -2,24 +2,42 @@
 
 pub mod adapters;
 pub mod app;
+pub mod assemblies;
 pub mod calculations;
 pub mod comment;
+pub mod compatibility;
 pub mod custom_widgets;
+pub mod history;
 pub mod holders;
 pub mod library;
 pub mod machine;
 pub mod magazine;
+pub mod materials;
+pub mod offset_formats;
+pub mod program_check;
 pub mod resources;
+pub mod slot;
 pub mod tools;
+pub mod transfer;
+pub mod turret;
 
 pub use adapters::*;
 pub use app::*;
+pub use assemblies::*;
 pub use calculations::*;
 pub use comment::*;
+pub use compatibility::*;
 pub use custom_widgets::*;
+pub use history::*;
 pub use holders::*;
 pub use library::*;
 pub use machine::*;
 pub use magazine::*;
+pub use materials::*;
+pub use offset_formats::*;
+pub use program_check::*;
 pub use resources::*;
+pub use slot::*;
 pub use tools::*;
+pub use transfer::*;
+pub use turret::*;
@@ -1,12 +1,14 @@
 use egui::scroll_area::ScrollBarVisibility;
 
-
 use crate::adapter::*;
-
+use crate::assembly::*;
+use crate::compatibility::*;
 use crate::holder::*;
+use crate::machine::load_refusal;
 
 use crate::reset_states;
 use crate::resources::*;
+use crate::slot::*;
 use crate::tool::*;
 use crate::ManagingApp;
 use egui_extras::*;
@@ -17,18 +19,46 @@ pub struct Library {
     pub tools: Vec<Tool>,
     pub holders: Vec<Holder>,
     pub adapters: Vec<Adapter>,
+    #[serde(default)]
+    pub assemblies: Vec<ToolAssembly>,
 }
 
 impl Library {
-    pub fn display(&mut self, ctx: &egui::Context) -> bool {
+    /// Takes everything out of a slot. A loaded assembly comes back as an assembly, loose
+    /// parts go to their own lists.
+    pub fn store_slot_contents(&mut self, slot: &mut Slot) {
+        if let Some(assembly) = ToolAssembly::unload_from(slot) {
+            self.assemblies.push(assembly);
+            return;
+        }
+        slot.assembly = None;
+        self.tools.extend(slot.tool.take());
+        self.holders.extend(slot.holder.take());
+        self.adapters.extend(slot.adapter.take());
+        slot.refresh_state();
+    }
+
+    /// Splits an assembly back into its tool, holder and adapter.
+    pub fn dismantle_assembly(&mut self, index: usize) {
+        let assembly = self.assemblies.remove(index);
+        self.tools.push(assembly.tool);
+        self.holders.push(assembly.holder);
+        self.adapters.extend(assembly.adapter);
+    }
+
+    /// Shows the library window, returns whether it is still open. Library items can be
+    /// dragged onto the magazine, a slot dragged onto the library is returned in `dropped_slot`.
+    pub fn display(&mut self, ctx: &egui::Context, dropped_slot: &mut Option<usize>) -> bool {
         let mut is_window_open = true;
-        egui::Window::new("Library")
+        let mut dismantle = None;
+        let window = egui::Window::new("Library")
             .open(&mut is_window_open)
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     ui.radio_value(&mut self.category, MagazineContentType::Tool, "Tool");
                     ui.radio_value(&mut self.category, MagazineContentType::Holder, "Holder");
                     ui.radio_value(&mut self.category, MagazineContentType::Adapter, "Adapter");
+                    ui.radio_value(&mut self.category, MagazineContentType::Assembly, "Assembly");
                 });
 
                 TableBuilder::new(ui)
@@ -50,7 +80,9 @@ impl Library {
                                             ui.label(format!("{}", i));
                                         });
                                         row.col(|ui| {
-                                            tool.display(ui);
+                                            drag_source(ui, DragItem::LibraryTool(i), |ui| {
+                                                tool.display(ui)
+                                            });
                                         });
                                     })
                                 }
@@ -62,7 +94,9 @@ impl Library {
                                             ui.label(format!("{}", i));
                                         });
                                         row.col(|ui| {
-                                            holder.display(ui);
+                                            drag_source(ui, DragItem::LibraryHolder(i), |ui| {
+                                                holder.display(ui)
+                                            });
                                         });
                                     })
                                 }
@@ -74,7 +108,36 @@ impl Library {
                                             ui.label(format!("{}", i));
                                         });
                                         row.col(|ui| {
-                                            adapter.display(ui);
+                                            drag_source(ui, DragItem::LibraryAdapter(i), |ui| {
+                                                adapter.display(ui)
+                                            });
+                                        });
+                                    })
+                                }
+                            }
+                            MagazineContentType::Assembly => {
+                                for (i, assembly) in self.assemblies.iter().enumerate() {
+                                    body.row(30.0, |mut row| {
+                                        row.col(|ui| {
+                                            ui.label(format!("{}", i));
+                                        });
+                                        row.col(|ui| {
+                                            ui.horizontal(|ui| {
+                                                if ui
+                                                    .button("Dismantle")
+                                                    .on_hover_text(
+                                                        "Return tool, holder and adapter separately",
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    dismantle = Some(i);
+                                                }
+                                                drag_source(
+                                                    ui,
+                                                    DragItem::LibraryAssembly(i),
+                                                    |ui| assembly.display(ui),
+                                                );
+                                            });
                                         });
                                     })
                                 }
@@ -82,10 +145,28 @@ impl Library {
                         };
                     });
             });
+        if let Some(response) = window {
+            if let Some(DragItem::Slot(pocket)) = response
+                .response
+                .dnd_release_payload::<DragItem>()
+                .as_deref()
+            {
+                *dropped_slot = Some(*pocket);
+            }
+        }
+        if let Some(index) = dismantle {
+            self.dismantle_assembly(index);
+        }
         is_window_open
     }
 }
 
+fn drag_source(ui: &mut egui::Ui, item: DragItem, add_contents: impl FnOnce(&mut egui::Ui)) {
+    ui.dnd_drag_source(egui::Id::new(("library_item", item)), item, add_contents)
+        .response
+        .on_hover_text("Drag onto a magazine slot to load");
+}
+
 pub fn move_tool_to_magazine(app: &mut ManagingApp) {
     let library_index = app.move_selections.selected_tool_index_library;
     let magazine_index = app.move_selections.selected_tool_index_magazine;
@@ -101,20 +182,50 @@ pub fn move_tool_to_magazine(app: &mut ManagingApp) {
     {
         let library_index = library_index.unwrap();
         let magazine_index = magazine_index.unwrap();
+        if let Some(refusal) = magazine.contents[magazine_index].blocked_refusal() {
+            app.move_selections.move_refusal = Some(refusal);
+            return;
+        }
         let library_tool = app.library.tools[library_index].clone();
-        let tool_in_magazine = magazine.contents[magazine_index].1.clone();
+        if let Some(conflict) = load_refusal(
+            machine.machine_type,
+            magazine,
+            magazine_index,
+            &library_tool,
+        ) {
+            app.move_selections.move_refusal = Some(conflict);
+            return;
+        }
+        let mut candidate = magazine.contents[magazine_index].clone();
+        candidate.tool = Some(library_tool.clone());
+        let review = review_mount(
+            &mut app.move_selections,
+            &candidate,
+            machine.spindle_interface,
+        );
+        if let MountReview::Blocked = review {
+            return;
+        }
+        let tool_in_magazine = magazine.contents[magazine_index].tool.clone();
 
         match tool_in_magazine {
             Some(tool) => {
-                magazine.contents[magazine_index].1 = Some(library_tool);
+                magazine.contents[magazine_index].tool = Some(library_tool);
                 app.library.tools.remove(library_index);
                 app.library.tools.push(tool);
             }
             None => {
-                magazine.contents[magazine_index].1 = Some(library_tool);
+                magazine.contents[magazine_index].tool = Some(library_tool);
                 app.library.tools.remove(library_index);
             }
         }
+        if let MountReview::Overridden(note) = review {
+            record_override(&mut magazine.contents[magazine_index], note);
+        }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -132,17 +243,21 @@ pub fn move_tool_to_library(app: &mut ManagingApp) {
 
     {
         let magazine_index = magazine_index.unwrap();
-        let magazine_tool = magazine.contents[magazine_index].1.clone();
+        let magazine_tool = magazine.contents[magazine_index].tool.clone();
 
         match magazine_tool {
             Some(tool) => {
                 app.library.tools.push(tool);
-                magazine.contents[magazine_index].1 = None;
+                magazine.contents[magazine_index].tool = None;
             }
             None => {
-                magazine.contents[magazine_index].1 = None;
+                magazine.contents[magazine_index].tool = None;
             }
         }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -167,7 +282,7 @@ pub fn select_tool_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
                 .show(ui, |ui| {
                     for (i, tool) in app.library.tools.iter().enumerate() {
                         tool.display(ui);
-                        if ui.button(format!("Move")).clicked() {
+                        if ui.button("Move").clicked() {
                             app.move_selections.selected_tool_index_library = Some(i);
                         }
                     }
@@ -175,6 +290,7 @@ pub fn select_tool_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
         });
     if !is_window_open {
         app.app_states.move_state = None;
+        app.move_selections.mount_violations.clear();
     }
 }
 
@@ -193,20 +309,41 @@ pub fn move_holder_to_magazine(app: &mut ManagingApp) {
     {
         let library_index = library_index.unwrap();
         let magazine_index = magazine_index.unwrap();
+        if let Some(refusal) = magazine.contents[magazine_index].blocked_refusal() {
+            app.move_selections.move_refusal = Some(refusal);
+            return;
+        }
         let library_holder = app.library.holders[library_index].clone();
-        let holder_in_magazine = magazine.contents[magazine_index].2.clone();
+        let mut candidate = magazine.contents[magazine_index].clone();
+        candidate.holder = Some(library_holder.clone());
+        let review = review_mount(
+            &mut app.move_selections,
+            &candidate,
+            machine.spindle_interface,
+        );
+        if let MountReview::Blocked = review {
+            return;
+        }
+        let holder_in_magazine = magazine.contents[magazine_index].holder.clone();
 
         match holder_in_magazine {
             Some(holder) => {
-                magazine.contents[magazine_index].2 = Some(library_holder);
+                magazine.contents[magazine_index].holder = Some(library_holder);
                 app.library.holders.remove(library_index);
                 app.library.holders.push(holder);
             }
             None => {
-                magazine.contents[magazine_index].2 = Some(library_holder);
+                magazine.contents[magazine_index].holder = Some(library_holder);
                 app.library.holders.remove(library_index);
             }
         }
+        if let MountReview::Overridden(note) = review {
+            record_override(&mut magazine.contents[magazine_index], note);
+        }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -224,17 +361,21 @@ pub fn move_holder_to_library(app: &mut ManagingApp) {
 
     {
         let magazine_index = magazine_index.unwrap();
-        let magazine_holder = magazine.contents[magazine_index].2.clone();
+        let magazine_holder = magazine.contents[magazine_index].holder.clone();
 
         match magazine_holder {
             Some(holder) => {
                 app.library.holders.push(holder);
-                magazine.contents[magazine_index].2 = None;
+                magazine.contents[magazine_index].holder = None;
             }
             None => {
-                magazine.contents[magazine_index].2 = None;
+                magazine.contents[magazine_index].holder = None;
             }
         }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -259,7 +400,7 @@ pub fn select_holder_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
                 .show(ui, |ui| {
                     for (i, holder) in app.library.holders.iter().enumerate() {
                         holder.display(ui);
-                        if ui.button(format!("Move")).clicked() {
+                        if ui.button("Move").clicked() {
                             app.move_selections.selected_holder_index_library = Some(i);
                         }
                     }
@@ -267,6 +408,7 @@ pub fn select_holder_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
         });
     if !is_window_open {
         app.app_states.move_state = None;
+        app.move_selections.mount_violations.clear();
     }
 }
 
@@ -285,20 +427,41 @@ pub fn move_adapter_to_magazine(app: &mut ManagingApp) {
     {
         let library_index = library_index.unwrap();
         let magazine_index = magazine_index.unwrap();
+        if let Some(refusal) = magazine.contents[magazine_index].blocked_refusal() {
+            app.move_selections.move_refusal = Some(refusal);
+            return;
+        }
         let library_adapter = app.library.adapters[library_index].clone();
-        let adapter_in_magazine = magazine.contents[magazine_index].3.clone();
+        let mut candidate = magazine.contents[magazine_index].clone();
+        candidate.adapter = Some(library_adapter.clone());
+        let review = review_mount(
+            &mut app.move_selections,
+            &candidate,
+            machine.spindle_interface,
+        );
+        if let MountReview::Blocked = review {
+            return;
+        }
+        let adapter_in_magazine = magazine.contents[magazine_index].adapter.clone();
 
         match adapter_in_magazine {
             Some(adapter) => {
-                magazine.contents[magazine_index].3 = Some(library_adapter);
+                magazine.contents[magazine_index].adapter = Some(library_adapter);
                 app.library.adapters.remove(library_index);
                 app.library.adapters.push(adapter);
             }
             None => {
-                magazine.contents[magazine_index].3 = Some(library_adapter);
+                magazine.contents[magazine_index].adapter = Some(library_adapter);
                 app.library.adapters.remove(library_index);
             }
         }
+        if let MountReview::Overridden(note) = review {
+            record_override(&mut magazine.contents[magazine_index], note);
+        }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -316,17 +479,21 @@ pub fn move_adapter_to_library(app: &mut ManagingApp) {
 
     {
         let magazine_index = magazine_index.unwrap();
-        let magazine_adapter = magazine.contents[magazine_index].3.clone();
+        let magazine_adapter = magazine.contents[magazine_index].adapter.clone();
 
         match magazine_adapter {
             Some(adapter) => {
                 app.library.adapters.push(adapter);
-                magazine.contents[magazine_index].3 = None;
+                magazine.contents[magazine_index].adapter = None;
             }
             None => {
-                magazine.contents[magazine_index].3 = None;
+                magazine.contents[magazine_index].adapter = None;
             }
         }
+        // Changing a single part breaks up an assembly loaded in the slot
+        magazine.contents[magazine_index].assembly = None;
+        magazine.contents[magazine_index].refresh_state();
+        magazine.refresh_blocked_pockets();
         app.display_magazine = magazine.clone();
         reset_states(app);
     }
@@ -351,7 +518,7 @@ pub fn select_adapter_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
                 .show(ui, |ui| {
                     for (i, adapter) in app.library.adapters.iter().enumerate() {
                         adapter.display(ui);
-                        if ui.button(format!("Move")).clicked() {
+                        if ui.button("Move").clicked() {
                             app.move_selections.selected_adapter_index_library = Some(i);
                         }
                     }
@@ -359,5 +526,97 @@ pub fn select_adapter_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
         });
     if !is_window_open {
         app.app_states.move_state = None;
+        app.move_selections.mount_violations.clear();
+    }
+}
+
+pub fn move_assembly_to_magazine(app: &mut ManagingApp) {
+    let library_index = app.move_selections.selected_assembly_index_library;
+    let magazine_index = app.move_selections.selected_assembly_index_magazine;
+
+    let (Some(library_index), Some(magazine_index)) = (library_index, magazine_index) else {
+        return;
+    };
+    let machine = &mut app.machines[app.selections.machine.unwrap()];
+    let magazine = &mut machine.magazines[machine.current_magazine.unwrap()];
+    if let Some(refusal) = magazine.contents[magazine_index].blocked_refusal() {
+        app.move_selections.move_refusal = Some(refusal);
+        return;
+    }
+    let tool = &app.library.assemblies[library_index].tool;
+    if let Some(conflict) = load_refusal(machine.machine_type, magazine, magazine_index, tool) {
+        app.move_selections.move_refusal = Some(conflict);
+        return;
+    }
+    let slot = &mut magazine.contents[magazine_index];
+    let mut candidate = Slot {
+        tool: None,
+        holder: None,
+        adapter: None,
+        ..slot.clone()
+    };
+    app.library.assemblies[library_index]
+        .clone()
+        .load_into(&mut candidate);
+    let review = review_mount(
+        &mut app.move_selections,
+        &candidate,
+        machine.spindle_interface,
+    );
+    if let MountReview::Blocked = review {
+        return;
+    }
+    let assembly = app.library.assemblies.remove(library_index);
+    app.library.store_slot_contents(slot);
+    assembly.load_into(slot);
+    if let MountReview::Overridden(note) = review {
+        record_override(slot, note);
+    }
+    magazine.refresh_blocked_pockets();
+    app.display_magazine = magazine.clone();
+    reset_states(app);
+}
+
+pub fn move_assembly_to_library(app: &mut ManagingApp) {
+    let Some(magazine_index) = app.move_selections.selected_assembly_index_magazine else {
+        return;
+    };
+    let machine = &mut app.machines[app.selections.machine.unwrap()];
+    let magazine = &mut machine.magazines[machine.current_magazine.unwrap()];
+    app.library
+        .store_slot_contents(&mut magazine.contents[magazine_index]);
+    magazine.refresh_blocked_pockets();
+    app.display_magazine = magazine.clone();
+    reset_states(app);
+}
+
+pub fn select_assembly_from_library(app: &mut ManagingApp, ctx: &egui::Context) {
+    if app.library.assemblies.is_empty() {
+        egui::Window::new("Library").show(ctx, |ui| {
+            ui.label("No assemblies in library");
+            if ui.button("Close").clicked() {
+                reset_states(app);
+            }
+        });
+        return;
+    }
+    let mut is_window_open = true;
+    egui::Window::new("Select assembly from library")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .scroll_bar_visibility(ScrollBarVisibility::AlwaysVisible)
+                .show(ui, |ui| {
+                    for (i, assembly) in app.library.assemblies.iter().enumerate() {
+                        assembly.display(ui);
+                        if ui.button("Load").clicked() {
+                            app.move_selections.selected_assembly_index_library = Some(i);
+                        }
+                    }
+                });
+        });
+    if !is_window_open {
+        app.app_states.move_state = None;
+        app.move_selections.mount_violations.clear();
     }
 }
@@ -1,16 +1,545 @@
+use std::fmt;
+
+use crate::calculations::calculations::CalculationType;
+use crate::compatibility::*;
 use crate::magazine::*;
+use crate::offset_transfer::OffsetFormat;
 use crate::reset_states;
-
-use crate::Comment;
+use crate::slot::*;
+use crate::tool::*;
+use crate::turret::*;
 use crate::ManagingApp;
+use strum::{EnumIter, IntoEnumIterator};
+
+/// Kind of machine, decides what can be mounted and which tools the app offers for it.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum MachineType {
+    #[default]
+    Mill,
+    Lathe,
+    MillTurn,
+    Router,
+}
+
+impl fmt::Display for MachineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MachineType::Mill => write!(f, "Mill"),
+            MachineType::Lathe => write!(f, "Lathe"),
+            MachineType::MillTurn => write!(f, "Mill-turn"),
+            MachineType::Router => write!(f, "Router"),
+        }
+    }
+}
+
+impl MachineType {
+    /// Tool categories that can be mounted on the machine.
+    pub fn tool_categories(&self) -> &'static [ToolCategory] {
+        match self {
+            MachineType::Mill | MachineType::Router => &[ToolCategory::Rotating],
+            MachineType::Lathe | MachineType::MillTurn => {
+                &[ToolCategory::Rotating, ToolCategory::LatheInsert]
+            }
+        }
+    }
+
+    pub fn accepts_tool(&self, tool: &Tool) -> bool {
+        self.tool_categories().contains(&tool.get_category())
+    }
+
+    /// Whether the calculation is relevant to the machine.
+    pub fn offers_calculation(&self, calculation: &CalculationType) -> bool {
+        match calculation {
+            CalculationType::GripClawPulling => {
+                matches!(self, MachineType::Lathe | MachineType::MillTurn)
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the control of such machines reads the offset format.
+    pub fn offers_offset_format(&self, format: OffsetFormat) -> bool {
+        match format {
+            OffsetFormat::FanucG10 | OffsetFormat::LinuxCncToolTable => true,
+            OffsetFormat::HeidenhainToolTable => {
+                matches!(self, MachineType::Mill | MachineType::MillTurn)
+            }
+        }
+    }
+
+    /// Magazine kind a new machine of this type starts with.
+    pub fn default_magazine_kind(&self) -> MagazineKind {
+        match self {
+            MachineType::Lathe => MagazineKind::Turret,
+            _ => MagazineKind::Chain,
+        }
+    }
+}
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Machine {
     pub name: String,
+    #[serde(default)]
+    pub machine_type: MachineType,
     pub magazines: Vec<Magazine>,
     pub number_of_magazines: usize,
-    pub magazine_size: usize,
+    pub magazine_size: usize, // Pockets of new magazines, follows the last resized magazine
     pub current_magazine: Option<usize>,
+    #[serde(default)]
+    pub chuck_m_codes: ChuckMCodes,
+    #[serde(default)]
+    pub spindle_interface: Option<ToolInterface>,
+    #[serde(default)]
+    pub limits: MachineLimits,
+}
+
+impl Machine {
+    /// Compares everything but the magazine being viewed.
+    pub fn same_setup(&self, other: &Machine) -> bool {
+        let Machine {
+            name,
+            machine_type,
+            magazines,
+            number_of_magazines,
+            magazine_size,
+            current_magazine: _,
+            chuck_m_codes,
+            spindle_interface,
+            limits,
+        } = self;
+        *name == other.name
+            && *machine_type == other.machine_type
+            && *magazines == other.magazines
+            && *number_of_magazines == other.number_of_magazines
+            && *magazine_size == other.magazine_size
+            && *chuck_m_codes == other.chuck_m_codes
+            && *spindle_interface == other.spindle_interface
+            && *limits == other.limits
+    }
+}
+
+/// What the spindle and magazine can take. Limits left at `None` are not checked.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MachineLimits {
+    pub max_tool_length: Option<f32>,   // Gauge length [mm]
+    pub max_tool_diameter: Option<f32>, // [mm] with loaded pockets on both sides
+    pub max_tool_diameter_empty_neighbours: Option<f32>, // [mm] with both neighbours empty
+    pub max_tool_weight: Option<f32>,   // [kg]
+    pub max_rpm: Option<f32>,
+    pub power: Option<f32>, // Spindle power [kW]
+}
+
+impl MachineLimits {
+    /// Largest tool diameter a pocket takes, given whether both neighbouring pockets are empty.
+    pub fn max_diameter(&self, neighbours_empty: bool) -> Option<f32> {
+        match (neighbours_empty, self.max_tool_diameter_empty_neighbours) {
+            (true, Some(diameter)) => Some(diameter),
+            _ => self.max_tool_diameter,
+        }
+    }
+
+    /// Lists the limits a loaded assembly exceeds.
+    pub fn violations(&self, slot: &Slot, neighbours_empty: bool) -> Vec<String> {
+        let mut violations = Vec::new();
+        let (Some(assembly), Some(tool)) = (&slot.assembly, &slot.tool) else {
+            return violations;
+        };
+        let gauge_length = assembly.dimensions.gauge_length(slot.adapter.is_some());
+        if let Some(max) = self.max_tool_length.filter(|max| gauge_length > *max) {
+            violations.push(format!(
+                "Gauge length {:.1} exceeds max tool length {:.1}",
+                gauge_length, max
+            ));
+        }
+        let diameter = tool.get_diameter();
+        if let Some(max) = self
+            .max_diameter(neighbours_empty)
+            .filter(|max| diameter > *max)
+        {
+            let condition = if neighbours_empty {
+                "with empty neighbours"
+            } else {
+                "with loaded neighbours"
+            };
+            violations.push(format!(
+                "Diameter {:.1} exceeds max {:.1} {}",
+                diameter, max, condition
+            ));
+        }
+        let weight = assembly.dimensions.weight;
+        if let Some(max) = self
+            .max_tool_weight
+            .filter(|max| weight > 0.0 && weight > *max)
+        {
+            violations.push(format!(
+                "Weight {:.1} kg exceeds max tool weight {:.1} kg",
+                weight, max
+            ));
+        }
+        violations
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("machine_limits_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                limit_row(ui, "Max tool length [mm]", &mut self.max_tool_length, 300.0);
+                limit_row(
+                    ui,
+                    "Max tool diameter [mm]",
+                    &mut self.max_tool_diameter,
+                    80.0,
+                );
+                limit_row(
+                    ui,
+                    "Max diameter, empty neighbours [mm]",
+                    &mut self.max_tool_diameter_empty_neighbours,
+                    125.0,
+                );
+                limit_row(ui, "Max tool weight [kg]", &mut self.max_tool_weight, 8.0);
+                limit_row(ui, "Max spindle speed [rpm]", &mut self.max_rpm, 12000.0);
+                limit_row(ui, "Spindle power [kW]", &mut self.power, 15.0);
+            });
+    }
+}
+
+/// Checkbox to enable a limit and a value to set it, `default` is used when enabling.
+fn limit_row(ui: &mut egui::Ui, label: &str, limit: &mut Option<f32>, default: f32) {
+    let mut enabled = limit.is_some();
+    ui.checkbox(&mut enabled, label);
+    match (enabled, limit.as_mut()) {
+        (true, Some(value)) => {
+            ui.add(egui::DragValue::new(value).clamp_range(0.0..=100000.0));
+        }
+        (true, None) => *limit = Some(default),
+        (false, _) => {
+            *limit = None;
+            ui.label("No limit");
+        }
+    }
+    ui.end_row();
+}
+
+/// Spindle interface and limits of the machine.
+pub fn machine_capabilities_edit(
+    spindle_interface: &mut Option<ToolInterface>,
+    limits: &mut MachineLimits,
+    ui: &mut egui::Ui,
+) {
+    interface_combo(ui, "Spindle interface", spindle_interface);
+    limits.edit(ui);
+}
+
+pub fn machine_capabilities(app: &mut ManagingApp, ctx: &egui::Context) {
+    let Some(machine) = app.selections.machine.and_then(|i| app.machines.get_mut(i)) else {
+        reset_states(app);
+        return;
+    };
+    let mut is_window_open = true;
+    egui::Window::new(format!("Capabilities of {}", machine.name))
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            machine_capabilities_edit(&mut machine.spindle_interface, &mut machine.limits, ui);
+        });
+    if !is_window_open {
+        reset_states(app);
+    }
+}
+
+/// M-codes the machine uses to open and close the main spindle chuck. They differ between
+/// builders, so bar pull programs take them from the machine.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ChuckMCodes {
+    pub open: u32,
+    pub close: u32,
+}
+
+impl Default for ChuckMCodes {
+    fn default() -> Self {
+        Self {
+            open: 69,
+            close: 68,
+        }
+    }
+}
+
+pub fn chuck_m_codes_edit(m_codes: &mut ChuckMCodes, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label("Chuck open: M");
+        ui.add(egui::DragValue::new(&mut m_codes.open).clamp_range(0..=999));
+        ui.label("Chuck close: M");
+        ui.add(egui::DragValue::new(&mut m_codes.close).clamp_range(0..=999));
+    });
+}
+
+/// Edit that takes pockets away from a machine, held until the user confirms it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendingRemoval {
+    ShrinkMagazine { magazine: usize, size: usize },
+    DeleteMagazine(usize),
+    DeleteMachine,
+}
+
+impl PendingRemoval {
+    fn question(&self, machine: &Machine) -> String {
+        match self {
+            PendingRemoval::ShrinkMagazine { magazine, size } => format!(
+                "Shrink {} to {} pockets?",
+                machine.magazines[*magazine].name, size
+            ),
+            PendingRemoval::DeleteMagazine(magazine) => {
+                format!("Delete {}?", machine.magazines[*magazine].name)
+            }
+            PendingRemoval::DeleteMachine => format!("Delete {}?", machine.name),
+        }
+    }
+
+    /// The pockets that disappear with the change.
+    fn removed_slots<'a>(&self, machine: &'a mut Machine) -> Vec<&'a mut Slot> {
+        match self {
+            PendingRemoval::ShrinkMagazine { magazine, size } => machine.magazines[*magazine]
+                .contents
+                .iter_mut()
+                .skip(*size)
+                .collect(),
+            PendingRemoval::DeleteMagazine(magazine) => {
+                machine.magazines[*magazine].contents.iter_mut().collect()
+            }
+            PendingRemoval::DeleteMachine => machine
+                .magazines
+                .iter_mut()
+                .flat_map(|magazine| magazine.contents.iter_mut())
+                .collect(),
+        }
+    }
+}
+
+fn new_magazine(machine: &Machine) -> Magazine {
+    let kind = machine
+        .magazines
+        .first()
+        .map(|magazine| magazine.kind)
+        .unwrap_or_default();
+    let name = match kind {
+        MagazineKind::Chain => format!("Magazine {}", machine.magazines.len()),
+        MagazineKind::Turret => format!("Turret {}", machine.magazines.len()),
+    };
+    Magazine {
+        name,
+        contents: (0..machine.magazine_size)
+            .map(|pocket| Slot::new_in(pocket, kind))
+            .collect(),
+        kind,
+    }
+}
+
+/// Moves the contents of the removed pockets to the library, then applies the change.
+fn apply_removal(app: &mut ManagingApp, machine_index: usize, removal: PendingRemoval) {
+    let machine = &mut app.machines[machine_index];
+    for slot in removal.removed_slots(machine) {
+        app.library.store_slot_contents(slot);
+    }
+    match removal {
+        PendingRemoval::ShrinkMagazine { magazine, size } => {
+            machine.magazines[magazine].resize(size);
+            machine.magazine_size = machine.magazines[magazine].contents.len();
+        }
+        PendingRemoval::DeleteMagazine(magazine) => {
+            machine.magazines.remove(magazine);
+            machine.number_of_magazines = machine.magazines.len();
+            machine.current_magazine = match machine.current_magazine {
+                Some(current) if current > magazine => Some(current - 1),
+                Some(current) if current == magazine => Some(0),
+                current => current,
+            };
+        }
+        PendingRemoval::DeleteMachine => {
+            app.machines.remove(machine_index);
+            app.selections.machine = None;
+            app.display_magazine = Magazine {
+                name: "None selected".to_string(),
+                contents: Vec::new(),
+                kind: MagazineKind::default(),
+            };
+            return;
+        }
+    }
+    if let Some(current) = machine.current_magazine {
+        app.display_magazine = machine.magazines[current].clone();
+    }
+}
+
+/// Lists what a pending removal moves to the library and applies it once confirmed.
+fn confirm_removal(app: &mut ManagingApp, machine_index: usize, ctx: &egui::Context) {
+    let Some(removal) = app.gui_singletons.pending_removal.clone() else {
+        return;
+    };
+    let mut confirmed = false;
+    let mut cancelled = false;
+    let machine = &mut app.machines[machine_index];
+    let question = removal.question(machine);
+    let moved: Vec<String> = removal
+        .removed_slots(machine)
+        .into_iter()
+        .filter_map(|slot| slot.contents_summary())
+        .collect();
+    egui::Window::new("Confirm")
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.strong(question);
+            if moved.is_empty() {
+                ui.label("No loaded pockets are removed.");
+            } else {
+                ui.label("These are moved back to the library:");
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for line in moved.iter() {
+                            ui.label(line);
+                        }
+                    });
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Confirm").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+    if confirmed {
+        app.gui_singletons.pending_removal = None;
+        apply_removal(app, machine_index, removal);
+    } else if cancelled {
+        app.gui_singletons.pending_removal = None;
+    }
+}
+
+pub fn edit_machine(app: &mut ManagingApp, ctx: &egui::Context) {
+    let Some(machine_index) = app.selections.machine else {
+        reset_states(app);
+        return;
+    };
+    let machine = &mut app.machines[machine_index];
+    let pending_removal = &mut app.gui_singletons.pending_removal;
+    let mut is_window_open = true;
+    let mut resized: Option<usize> = None;
+    egui::Window::new("Edit machine")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            ui.add_enabled_ui(pending_removal.is_none(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Machine name:");
+                    ui.text_edit_singleline(&mut machine.name);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Machine type:");
+                    for option in MachineType::iter() {
+                        ui.radio_value(&mut machine.machine_type, option, option.to_string());
+                    }
+                });
+                let unfit = machine
+                    .magazines
+                    .iter()
+                    .flat_map(|magazine| magazine.contents.iter())
+                    .filter_map(|slot| slot.tool.as_ref())
+                    .filter(|tool| !machine.machine_type.accepts_tool(tool))
+                    .count();
+                if unfit > 0 {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!(
+                            "{} loaded tools cannot be mounted on a {}",
+                            unfit, machine.machine_type
+                        ),
+                    );
+                }
+                ui.separator();
+                let single_magazine = machine.magazines.len() == 1;
+                egui::Grid::new("edit_machine_magazines_grid")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (i, magazine) in machine.magazines.iter_mut().enumerate() {
+                            ui.text_edit_singleline(&mut magazine.name);
+                            let mut size = magazine.contents.len();
+                            if ui
+                                .add(egui::DragValue::new(&mut size).clamp_range(1..=100))
+                                .on_hover_text("Pockets")
+                                .changed()
+                            {
+                                if size >= magazine.min_size() {
+                                    magazine.resize(size);
+                                    resized = Some(magazine.contents.len());
+                                } else {
+                                    *pending_removal =
+                                        Some(PendingRemoval::ShrinkMagazine { magazine: i, size });
+                                }
+                            }
+                            if ui
+                                .add_enabled(!single_magazine, egui::Button::new("Delete"))
+                                .on_disabled_hover_text("A machine needs a magazine")
+                                .clicked()
+                            {
+                                *pending_removal = Some(PendingRemoval::DeleteMagazine(i));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if ui.button("Add magazine").clicked() {
+                    let magazine = new_magazine(machine);
+                    machine.magazines.push(magazine);
+                    machine.number_of_magazines = machine.magazines.len();
+                }
+                ui.separator();
+                if ui
+                    .button("Delete machine")
+                    .on_hover_text("Loaded contents are moved back to the library")
+                    .clicked()
+                {
+                    *pending_removal = Some(PendingRemoval::DeleteMachine);
+                }
+            });
+        });
+    if let Some(size) = resized {
+        machine.magazine_size = size;
+        if let Some(current) = machine.current_magazine {
+            app.display_magazine = machine.magazines[current].clone();
+        }
+    }
+    confirm_removal(app, machine_index, ctx);
+    if app.selections.machine.is_none() {
+        reset_states(app);
+        return;
+    }
+    if !is_window_open {
+        app.gui_singletons.pending_removal = None;
+        reset_states(app);
+    }
+}
+
+/// Explains why `tool` cannot be loaded into `pocket` of a machine of the given type at all.
+pub fn load_refusal(
+    machine_type: MachineType,
+    magazine: &Magazine,
+    pocket: usize,
+    tool: &Tool,
+) -> Option<String> {
+    if !machine_type.accepts_tool(tool) {
+        return Some(format!(
+            "{} is a {} and cannot be mounted on a {}",
+            tool.get_name(),
+            tool.get_type(),
+            machine_type
+        ));
+    }
+    magazine.oversize_conflict(pocket, tool)
+}
+
+pub fn selected_machine_type(app: &ManagingApp) -> Option<MachineType> {
+    app.selections
+        .machine
+        .and_then(|i| app.machines.get(i))
+        .map(|machine| machine.machine_type)
 }
 
 pub fn select_machine(app: &mut ManagingApp, ui: &mut egui::Ui) {
@@ -19,11 +548,8 @@ pub fn select_machine(app: &mut ManagingApp, ui: &mut egui::Ui) {
         return;
     }
     let mut name = "".to_string();
-    match app.selections.machine {
-        Some(index) => {
-            name = app.machines[index].name.clone();
-        }
-        None => {}
+    if let Some(index) = app.selections.machine {
+        name = app.machines[index].name.clone();
     }
 
     egui::ComboBox::from_label("Select machine")
@@ -50,6 +576,18 @@ pub fn add_machine(app: &mut ManagingApp, ctx: &egui::Context) {
                 ui.label("Machine name:");
                 ui.text_edit_singleline(&mut app.gui_singletons.machine.name);
             });
+            ui.horizontal(|ui| {
+                ui.label("Machine type:");
+                let machine_type = &mut app.gui_singletons.machine.machine_type;
+                for option in MachineType::iter() {
+                    if ui
+                        .radio_value(machine_type, option, option.to_string())
+                        .changed()
+                    {
+                        app.gui_singletons.magazine_kind = option.default_magazine_kind();
+                    }
+                }
+            });
             ui.horizontal(|ui| {
                 ui.label("Number of magazines:");
                 ui.add(
@@ -57,11 +595,34 @@ pub fn add_machine(app: &mut ManagingApp, ctx: &egui::Context) {
                         .text("magazines"),
                 );
             });
+            ui.horizontal(|ui| {
+                ui.label("Magazine kind:");
+                for kind in MagazineKind::iter() {
+                    ui.radio_value(
+                        &mut app.gui_singletons.magazine_kind,
+                        kind,
+                        kind.to_string(),
+                    );
+                }
+            });
+            let unit = match app.gui_singletons.magazine_kind {
+                MagazineKind::Chain => "slots",
+                MagazineKind::Turret => "stations",
+            };
             ui.horizontal(|ui| {
                 ui.label("Magazine size:");
                 ui.add(
                     egui::Slider::new(&mut app.gui_singletons.machine.magazine_size, 1..=100)
-                        .text("slots"),
+                        .text(unit),
+                );
+            });
+            chuck_m_codes_edit(&mut app.gui_singletons.machine.chuck_m_codes, ui);
+            let new_machine = &mut app.gui_singletons.machine;
+            ui.collapsing("Capabilities", |ui| {
+                machine_capabilities_edit(
+                    &mut new_machine.spindle_interface,
+                    &mut new_machine.limits,
+                    ui,
                 );
             });
             ui.horizontal(|ui| {
@@ -72,24 +633,31 @@ pub fn add_machine(app: &mut ManagingApp, ctx: &egui::Context) {
             if should_add_machine {
                 let mut magazines: Vec<Magazine> = Vec::new();
                 for _ in 0..app.gui_singletons.machine.number_of_magazines {
+                    let kind = app.gui_singletons.magazine_kind;
                     let mut current_magazine = Magazine {
                         name: "".to_string(),
                         contents: Vec::new(),
+                        kind,
                     };
                     for i in 0..app.gui_singletons.machine.magazine_size {
-                        current_magazine
-                            .contents
-                            .push((i, None, None, None, Comment::default()));
+                        current_magazine.contents.push(Slot::new_in(i, kind));
                     }
-                    current_magazine.name = format!("Magazine {}", magazines.len());
+                    current_magazine.name = match kind {
+                        MagazineKind::Chain => format!("Magazine {}", magazines.len()),
+                        MagazineKind::Turret => format!("Turret {}", magazines.len()),
+                    };
                     magazines.push(current_magazine);
                 }
                 let machine = Machine {
                     name: app.gui_singletons.machine.name.clone(),
+                    machine_type: app.gui_singletons.machine.machine_type,
                     magazines: magazines.clone(),
                     number_of_magazines: app.gui_singletons.machine.number_of_magazines,
                     magazine_size: app.gui_singletons.machine.magazine_size,
                     current_magazine: Some(0),
+                    chuck_m_codes: app.gui_singletons.machine.chuck_m_codes.clone(),
+                    spindle_interface: app.gui_singletons.machine.spindle_interface,
+                    limits: app.gui_singletons.machine.limits.clone(),
                 };
                 app.machines.push(machine.clone());
                 let machine_index = app.machines.len() - 1;
@@ -101,10 +669,14 @@ pub fn add_machine(app: &mut ManagingApp, ctx: &egui::Context) {
                 app.display_magazine.contents = magazines[0].contents.clone();
                 app.gui_singletons.machine = Machine {
                     name: "Machine".to_string(),
+                    machine_type: MachineType::default(),
                     magazines: Vec::new(),
                     number_of_magazines: 1,
                     magazine_size: 1,
                     current_magazine: None,
+                    chuck_m_codes: ChuckMCodes::default(),
+                    spindle_interface: None,
+                    limits: MachineLimits::default(),
                 };
 
                 should_add_machine = false;
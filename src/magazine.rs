@@ -1,25 +1,148 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
-use crate::adapter::*;
-use crate::holder::*;
-
-use crate::comment::*;
+use crate::geometry::*;
 use crate::reset_states;
+use crate::slot::*;
 use crate::tool::*;
+use crate::transfer::start_transfer;
+use crate::turret::*;
+use crate::DragItem;
 use crate::ManagingApp;
 use crate::MoveStates;
 use crate::ToolState;
+use egui::Color32;
 use egui_extras::*;
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct Magazine {
     pub name: String,
-    pub contents: Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
+    #[serde(deserialize_with = "deserialize_slots")]
+    pub contents: Vec<Slot>,
+    #[serde(default)]
+    pub kind: MagazineKind,
+}
+
+impl Magazine {
+    /// Smallest size the magazine can shrink to without dropping loaded pockets.
+    pub fn min_size(&self) -> usize {
+        self.contents
+            .iter()
+            .rposition(|slot| slot.is_loaded())
+            .map_or(1, |pocket| pocket + 1)
+    }
+
+    /// Adds empty pockets at the end or removes pockets from the end, never below `min_size`.
+    pub fn resize(&mut self, size: usize) {
+        let size = size.max(self.min_size());
+        self.contents.truncate(size);
+        for pocket in self.contents.len()..size {
+            self.contents.push(Slot::new_in(pocket, self.kind));
+        }
+        self.refresh_blocked_pockets();
+    }
+
+    /// Pockets an oversized tool in `pocket` blocks. Chains, carousels and turret discs are
+    /// closed loops, so the first and last pocket are neighbours.
+    pub fn blocked_pockets(&self, pocket: usize, oversize: &Oversize) -> Vec<usize> {
+        let len = self.contents.len();
+        if len == 0 {
+            return Vec::new();
+        }
+        let left = (1..=oversize.left.min(len - 1)).map(|offset| (pocket + len - offset) % len);
+        let right = (1..=oversize.right.min(len - 1)).map(|offset| (pocket + offset) % len);
+        let mut blocked: Vec<usize> = left.chain(right).filter(|p| *p != pocket).collect();
+        blocked.sort_unstable();
+        blocked.dedup();
+        blocked
+    }
+
+    /// Explains why `tool` cannot be loaded into `pocket`, if it would block loaded pockets.
+    pub fn oversize_conflict(&self, pocket: usize, tool: &Tool) -> Option<String> {
+        let loaded: Vec<String> = self
+            .blocked_pockets(pocket, &tool.get_oversize())
+            .into_iter()
+            .filter(|p| self.contents[*p].is_loaded())
+            .map(|p| p.to_string())
+            .collect();
+        if loaded.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{} is oversized and needs slot {} empty",
+            tool.get_name(),
+            loaded.join(", ")
+        ))
+    }
+
+    /// Explains an oversize conflict involving `pocket`: its tool blocking loaded pockets, or
+    /// an oversized tool elsewhere reaching into it while it is loaded.
+    pub fn oversize_conflict_at(&self, pocket: usize) -> Option<String> {
+        let slot = &self.contents[pocket];
+        if let Some(conflict) = slot
+            .tool
+            .as_ref()
+            .and_then(|tool| self.oversize_conflict(pocket, tool))
+        {
+            return Some(conflict);
+        }
+        if !slot.is_loaded() {
+            return None;
+        }
+        self.contents.iter().find_map(|other| {
+            let tool = other.tool.as_ref()?;
+            self.blocked_pockets(other.pocket, &tool.get_oversize())
+                .contains(&pocket)
+                .then(|| {
+                    format!(
+                        "{} is oversized and needs slot {} empty",
+                        tool.get_name(),
+                        pocket
+                    )
+                })
+        })
+    }
+
+    /// Exchanges what is mounted in two pockets, see `Slot::exchange_contents`.
+    pub fn swap_contents(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let (first, second) = (a.min(b), a.max(b));
+        let (left, right) = self.contents.split_at_mut(second);
+        left[first].exchange_contents(&mut right[0]);
+        self.refresh_blocked_pockets();
+    }
+
+    /// Blocks the pockets next to every loaded oversized tool and releases pockets no tool
+    /// blocks any longer. Call after the contents changed.
+    pub fn refresh_blocked_pockets(&mut self) {
+        for slot in self.contents.iter_mut() {
+            if slot.blocked_by.take().is_some() {
+                slot.state = SlotState::Empty;
+                slot.refresh_state();
+            }
+        }
+        let blocking: Vec<(usize, Vec<usize>)> = self
+            .contents
+            .iter()
+            .filter_map(|slot| {
+                let tool = slot.tool.as_ref()?;
+                Some((
+                    slot.pocket,
+                    self.blocked_pockets(slot.pocket, &tool.get_oversize()),
+                ))
+            })
+            .collect();
+        for (pocket, blocked) in blocking {
+            for p in blocked {
+                let slot = &mut self.contents[p];
+                if !slot.is_loaded() {
+                    slot.blocked_by = Some(pocket);
+                    slot.state = SlotState::Blocked;
+                }
+            }
+        }
+    }
 }
 
 pub fn select_magazine(app: &mut ManagingApp, ui: &mut egui::Ui) {
@@ -46,194 +169,146 @@ pub fn select_magazine(app: &mut ManagingApp, ui: &mut egui::Ui) {
         });
 }
 
-pub fn get_sorted_by_slot(
-    contents: &mut Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
-    filter: Option<ToolState>,
-) -> Vec<(
-    usize,
-    Option<Tool>,
-    Option<Holder>,
-    Option<Adapter>,
-    Comment,
-)> {
-    let mut filtered = match filter {
+pub fn get_sorted_by_slot(contents: &[Slot], filter: Option<ToolState>) -> Vec<Slot> {
+    let mut sorted = match filter {
         Some(ToolState::Rotating) => {
             get_filtered_by_tool_category(contents, ToolCategory::Rotating)
         }
         Some(ToolState::Insert) => {
             get_filtered_by_tool_category(contents, ToolCategory::LatheInsert)
         }
-        _ => contents.clone(),
+        _ => contents.to_vec(),
     };
 
-    let mut sorted: Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )> = filtered
-        .iter_mut()
-        .map(|(i, tool, holder, adapter, comment)| {
-            (
-                *i,
-                tool.clone(),
-                holder.clone(),
-                adapter.clone(),
-                comment.clone(),
-            )
-        })
-        .collect();
-
-    sorted.sort_by(|(i_a, ..), (i_b, ..)| i_a.partial_cmp(i_b).unwrap_or(Ordering::Equal));
+    sorted.sort_by_key(|slot| slot.pocket);
     sorted
 }
 
-pub fn get_sorted_by_tool_diameter(
-    contents: &mut Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
-) -> Vec<(
-    usize,
-    Option<Tool>,
-    Option<Holder>,
-    Option<Adapter>,
-    Comment,
-)> {
-    let mut filtered: Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )> = contents
-        .iter_mut()
-        .filter(|(_u32, tool, _holder, _adapter, _comment)| {
-            tool.clone()
-                .map(|t| t.get_category() == ToolCategory::Rotating)
-                .unwrap_or(false)
-        })
-        .map(|(i, tool, holder, adapter, comment)| {
-            (
-                *i,
-                tool.clone(),
-                holder.clone(),
-                adapter.clone(),
-                comment.clone(),
-            )
-        })
-        .collect();
+pub fn get_sorted_by_tool_diameter(contents: &[Slot]) -> Vec<Slot> {
+    let mut filtered = get_filtered_by_tool_category(contents, ToolCategory::Rotating);
 
-    filtered.sort_by(|(_i_a, tool_a, ..), (_i_b, tool_b, ..)| {
-        tool_a
-            .clone()
-            .unwrap()
-            .get_diameter()
-            .partial_cmp(&tool_b.clone().unwrap().get_diameter())
+    filtered.sort_by(|slot_a, slot_b| {
+        slot_a
+            .tool
+            .as_ref()
+            .map(|tool| tool.get_diameter())
+            .partial_cmp(&slot_b.tool.as_ref().map(|tool| tool.get_diameter()))
             .unwrap_or(Ordering::Equal)
     });
     filtered
 }
 
-pub fn get_sorted_by_degree(
-    contents: &mut Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
-) -> Vec<(
-    usize,
-    Option<Tool>,
-    Option<Holder>,
-    Option<Adapter>,
-    Comment,
-)> {
-    let mut filtered: Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )> = contents
-        .iter_mut()
-        .filter(|(_u32, tool, _holder, _adapter, _comment)| {
-            tool.clone()
-                .map(|t| t.get_category() == ToolCategory::LatheInsert)
-                .unwrap_or(false)
-        })
-        .map(|(i, tool, holder, adapter, comment)| {
-            (
-                *i,
-                tool.clone(),
-                holder.clone(),
-                adapter.clone(),
-                comment.clone(),
-            )
+/// Groups rotating tools by type, then orders them by diameter and their type's own size.
+pub fn get_sorted_by_tool_type(contents: &[Slot]) -> Vec<Slot> {
+    let mut filtered = get_filtered_by_tool_category(contents, ToolCategory::Rotating);
+    let key = |slot: &Slot| {
+        slot.tool
+            .as_ref()
+            .map(|tool| (tool.get_type(), tool.get_diameter(), tool.get_sort_detail()))
+    };
+
+    filtered.sort_by(|slot_a, slot_b| {
+        key(slot_a)
+            .partial_cmp(&key(slot_b))
+            .unwrap_or(Ordering::Equal)
+    });
+    filtered
+}
+
+/// Orders slots by a geometry value, tools without a geometry are left out.
+pub fn get_sorted_by_geometry(contents: &[Slot], key: impl Fn(&ToolGeometry) -> f32) -> Vec<Slot> {
+    let mut filtered: Vec<Slot> = contents
+        .iter()
+        .filter(|slot| {
+            slot.tool
+                .as_ref()
+                .is_some_and(|tool| tool.get_geometry().is_some())
         })
+        .cloned()
         .collect();
+    let value = |slot: &Slot| {
+        slot.tool
+            .as_ref()
+            .and_then(|tool| tool.get_geometry())
+            .map(&key)
+    };
+
+    filtered.sort_by(|slot_a, slot_b| {
+        value(slot_a)
+            .partial_cmp(&value(slot_b))
+            .unwrap_or(Ordering::Equal)
+    });
+    filtered
+}
 
-    filtered.sort_by(|(_i_a, tool_a, ..), (_i_b, tool_b, ..)| {
-        tool_a
-            .clone()
-            .unwrap()
-            .get_degree()
-            .partial_cmp(&tool_b.clone().unwrap().get_degree())
+pub fn get_sorted_by_degree(contents: &[Slot]) -> Vec<Slot> {
+    let mut filtered = get_filtered_by_tool_category(contents, ToolCategory::LatheInsert);
+
+    filtered.sort_by(|slot_a, slot_b| {
+        slot_a
+            .tool
+            .as_ref()
+            .map(|tool| tool.get_degree())
+            .partial_cmp(&slot_b.tool.as_ref().map(|tool| tool.get_degree()))
             .unwrap_or(Ordering::Equal)
     });
     filtered
 }
 
-pub fn get_filtered_by_tool_category(
-    contents: &mut Vec<(
-        usize,
-        Option<Tool>,
-        Option<Holder>,
-        Option<Adapter>,
-        Comment,
-    )>,
-    category: ToolCategory,
-) -> Vec<(
-    usize,
-    Option<Tool>,
-    Option<Holder>,
-    Option<Adapter>,
-    Comment,
-)> {
+pub fn get_filtered_by_tool_material(contents: &[Slot], material: ToolMaterial) -> Vec<Slot> {
     contents
-        .iter_mut()
-        .filter(|(_usize, tool, _holder, _adapter, _comment)| {
-            tool.clone()
-                .map(|t| t.get_category() == category)
-                .unwrap_or(false)
+        .iter()
+        .filter(|slot| {
+            slot.tool
+                .as_ref()
+                .and_then(|tool| tool.get_geometry())
+                .is_some_and(|geometry| geometry.material == material)
         })
-        .map(|(i, tool, holder, adapter, comment)| {
-            (
-                *i,
-                tool.clone(),
-                holder.clone(),
-                adapter.clone(),
-                comment.clone(),
-            )
+        .cloned()
+        .collect()
+}
+
+pub fn get_filtered_by_tool_category(contents: &[Slot], category: ToolCategory) -> Vec<Slot> {
+    contents
+        .iter()
+        .filter(|slot| {
+            slot.tool
+                .as_ref()
+                .map(|tool| tool.get_category() == category)
+                .unwrap_or(false)
         })
+        .cloned()
         .collect()
 }
 
 pub fn display_magazine(app: &mut ManagingApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
     ui.label(app.display_magazine.name.clone());
+    let mut edited_offsets: Vec<(usize, ToolOffsets)> = Vec::new();
+    let mut dropped: Option<(DragItem, usize)> = None;
+    let mut transfer_pocket: Option<usize> = None;
+    // Checked on the machine's magazine, the displayed copy may be filtered or sorted
+    let limit_violations: HashMap<usize, Vec<String>> = app
+        .selections
+        .machine
+        .and_then(|i| app.machines.get(i))
+        .and_then(|machine| {
+            let magazine = machine.magazines.get(machine.current_magazine?)?;
+            let contents = &magazine.contents;
+            Some(
+                contents
+                    .iter()
+                    .map(|slot| {
+                        let violations = machine
+                            .limits
+                            .violations(slot, neighbours_empty(contents, slot.pocket));
+                        (slot.pocket, violations)
+                    })
+                    .collect(),
+            )
+        })
+        .unwrap_or_default();
     TableBuilder::new(ui)
-        .columns(Column::auto().resizable(true).clip(false), 5)
+        .columns(Column::auto().resizable(true).clip(false), 7)
         .header(20.0, |mut header| {
             header.col(|ui| {
                 ui.heading("Slot");
@@ -247,30 +322,100 @@ pub fn display_magazine(app: &mut ManagingApp, ui: &mut egui::Ui, _ctx: &egui::C
             header.col(|ui| {
                 ui.heading("Adapter");
             });
+            header.col(|ui| {
+                ui.heading("Length (H)")
+                    .on_hover_text("Geometry + wear = total");
+            });
+            header.col(|ui| {
+                ui.heading("Radius (D)")
+                    .on_hover_text("Geometry + wear = total");
+            });
             header.col(|ui| {
                 ui.heading("Comment");
             });
         })
         .body(|mut body| {
-            for (_i, (index, tool, holder, adapter, comment)) in
-                app.display_magazine.contents.iter().enumerate()
-            {
+            for slot in app.display_magazine.contents.iter_mut() {
+                let index = slot.pocket;
+                let wear_limit = slot
+                    .tool
+                    .as_ref()
+                    .and_then(|tool| app.gui_singletons.wear_limits.get(&tool.get_category()))
+                    .cloned();
                 body.row(30.0, |mut row| {
                     row.col(|ui| {
-                        ui.label(format!("Slot {}", index));
+                        ui.horizontal(|ui| {
+                            ui.dnd_drag_source(
+                                egui::Id::new(("magazine_slot", index)),
+                                DragItem::Slot(index),
+                                |ui| ui.label("☰"),
+                            )
+                            .response
+                            .on_hover_text(
+                                "Drag onto another slot to swap, or onto the library to unload",
+                            );
+                            if ui.button("Edit").clicked() {
+                                app.move_selections.selected_slot_index_magazine = Some(index);
+                                app.app_states.move_state = Some(MoveStates::EditSlot);
+                            }
+                            if ui
+                                .button("Move")
+                                .on_hover_text("Move or swap with a slot of any magazine")
+                                .clicked()
+                            {
+                                transfer_pocket = Some(index);
+                            }
+                            if slot.assembly.is_some() {
+                                if ui
+                                    .button("Unload")
+                                    .on_hover_text("Return the assembly to the library")
+                                    .clicked()
+                                {
+                                    app.move_selections.selected_assembly_index_magazine =
+                                        Some(index);
+                                    app.app_states.move_state = Some(MoveStates::AssemblyToLibrary);
+                                }
+                            } else if ui
+                                .button("Load")
+                                .on_hover_text("Load an assembly from the library")
+                                .clicked()
+                            {
+                                app.move_selections.selected_assembly_index_magazine = Some(index);
+                                app.app_states.move_state = Some(MoveStates::AssemblyToMagazine);
+                            }
+                            slot.display(ui);
+                            if let Some(violations) = limit_violations
+                                .get(&slot.pocket)
+                                .filter(|violations| !violations.is_empty())
+                            {
+                                ui.colored_label(Color32::RED, "⚠ Limits")
+                                    .on_hover_text(violations.join("\n"));
+                            }
+                        });
                     });
                     row.col(|ui| {
                         ui.horizontal(|ui| {
                             if ui.button("Add").clicked() {
-                                app.move_selections.selected_tool_index_magazine = Some(*index);
+                                app.move_selections.selected_tool_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::ToolToMagazine);
                             }
                             if ui.button("Remove").clicked() {
-                                app.move_selections.selected_tool_index_magazine = Some(*index);
+                                app.move_selections.selected_tool_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::ToolToLibrary);
                             }
-                            if let Some(tool) = tool {
+                            if let Some(tool) = &slot.tool {
                                 tool.display(ui);
+                                if let (ToolCategory::LatheInsert, Some(station)) =
+                                    (tool.get_category(), &slot.station)
+                                {
+                                    ui.label(station.orientation_text())
+                                        .on_hover_text("Orientation of the station");
+                                }
+                            } else if let Some(pocket) = slot.blocked_by {
+                                ui.colored_label(
+                                    Color32::GRAY,
+                                    format!("Blocked by oversized tool in slot {}", pocket),
+                                );
                             } else {
                                 ui.label("Empty");
                             }
@@ -279,14 +424,14 @@ pub fn display_magazine(app: &mut ManagingApp, ui: &mut egui::Ui, _ctx: &egui::C
                     row.col(|ui| {
                         ui.horizontal(|ui| {
                             if ui.button("Add").clicked() {
-                                app.move_selections.selected_holder_index_magazine = Some(*index);
+                                app.move_selections.selected_holder_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::HolderToMagazine);
                             }
                             if ui.button("Remove").clicked() {
-                                app.move_selections.selected_holder_index_magazine = Some(*index);
+                                app.move_selections.selected_holder_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::HolderToLibrary);
                             }
-                            if let Some(holder) = holder {
+                            if let Some(holder) = &slot.holder {
                                 holder.display(ui);
                             } else {
                                 ui.label("Empty");
@@ -296,33 +441,179 @@ pub fn display_magazine(app: &mut ManagingApp, ui: &mut egui::Ui, _ctx: &egui::C
                     row.col(|ui| {
                         ui.horizontal(|ui| {
                             if ui.button("Add").clicked() {
-                                app.move_selections.selected_adapter_index_magazine = Some(*index);
+                                app.move_selections.selected_adapter_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::AdapterToMagazine);
                             }
                             if ui.button("Remove").clicked() {
-                                app.move_selections.selected_adapter_index_magazine = Some(*index);
+                                app.move_selections.selected_adapter_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::AdapterToLibrary);
                             }
-                            if let Some(adapter) = adapter {
+                            if let Some(adapter) = &slot.adapter {
                                 adapter.display(ui);
                             } else {
                                 ui.label("Empty");
                             }
                         });
                     });
+                    row.col(|ui| {
+                        if offset_edit(
+                            ui,
+                            &mut slot.offsets.length_geometry,
+                            &mut slot.offsets.length_wear,
+                            wear_limit.as_ref().map(|limit| limit.length),
+                        ) {
+                            edited_offsets.push((index, slot.offsets.clone()));
+                        }
+                    });
+                    row.col(|ui| {
+                        if offset_edit(
+                            ui,
+                            &mut slot.offsets.radius_geometry,
+                            &mut slot.offsets.radius_wear,
+                            wear_limit.as_ref().map(|limit| limit.radius),
+                        ) {
+                            edited_offsets.push((index, slot.offsets.clone()));
+                        }
+                    });
                     row.col(|ui| {
                         ui.horizontal(|ui| {
                             ui.set_width(100.0);
                             if ui.button("Edit").clicked() {
-                                app.move_selections.selected_comment_index_magazine = Some(*index);
+                                app.move_selections.selected_comment_index_magazine = Some(index);
                                 app.app_states.move_state = Some(MoveStates::EditComment);
                             }
-                            comment.display(ui);
+                            slot.comment.display(ui);
                         });
                     });
+                    if let Some(item) = row.response().dnd_release_payload::<DragItem>() {
+                        dropped = Some((*item, index));
+                    }
                 });
             }
         });
+
+    if let Some((item, pocket)) = dropped {
+        start_drop(app, item, Some(pocket));
+    }
+    if let Some(pocket) = transfer_pocket {
+        start_transfer(app, pocket);
+    }
+
+    // The table edits the displayed copy, so write changed offsets back to the machine
+    if edited_offsets.is_empty() {
+        return;
+    }
+    if let Some(machine_index) = app.selections.machine {
+        let machine = &mut app.machines[machine_index];
+        if let Some(magazine_index) = machine.current_magazine {
+            let magazine = &mut machine.magazines[magazine_index];
+            for (pocket, offsets) in edited_offsets {
+                magazine.contents[pocket].offsets = offsets;
+            }
+        }
+    }
+}
+
+/// Whether the pockets on both sides of `pocket` hold nothing. The first and last pocket are
+/// neighbours, see `Magazine::blocked_pockets`.
+pub fn neighbours_empty(contents: &[Slot], pocket: usize) -> bool {
+    let len = contents.len();
+    if len < 2 {
+        return true;
+    }
+    [(pocket + len - 1) % len, (pocket + 1) % len]
+        .into_iter()
+        .filter(|p| *p != pocket)
+        .all(|p| !contents[p].is_loaded())
+}
+
+/// Starts the move a drop stands for, the existing move states carry it out. A `pocket` of
+/// `None` is a drop on the library.
+pub fn start_drop(app: &mut ManagingApp, item: DragItem, pocket: Option<usize>) {
+    if app.app_states.move_state.is_some() {
+        return;
+    }
+    let selections = &mut app.move_selections;
+    let state = match (item, pocket) {
+        (DragItem::LibraryTool(i), Some(p)) => {
+            selections.selected_tool_index_library = Some(i);
+            selections.selected_tool_index_magazine = Some(p);
+            MoveStates::ToolToMagazine
+        }
+        (DragItem::LibraryHolder(i), Some(p)) => {
+            selections.selected_holder_index_library = Some(i);
+            selections.selected_holder_index_magazine = Some(p);
+            MoveStates::HolderToMagazine
+        }
+        (DragItem::LibraryAdapter(i), Some(p)) => {
+            selections.selected_adapter_index_library = Some(i);
+            selections.selected_adapter_index_magazine = Some(p);
+            MoveStates::AdapterToMagazine
+        }
+        (DragItem::LibraryAssembly(i), Some(p)) => {
+            selections.selected_assembly_index_library = Some(i);
+            selections.selected_assembly_index_magazine = Some(p);
+            MoveStates::AssemblyToMagazine
+        }
+        (DragItem::Slot(from), Some(to)) if from != to => {
+            selections.selected_slot_index_magazine = Some(from);
+            selections.swap_target_index_magazine = Some(to);
+            MoveStates::SwapSlots
+        }
+        // Unloading an assembly stores whatever the slot holds
+        (DragItem::Slot(from), None) => {
+            selections.selected_assembly_index_magazine = Some(from);
+            MoveStates::AssemblyToLibrary
+        }
+        _ => return,
+    };
+    selections.from_drop = true;
+    app.app_states.move_state = Some(state);
+}
+
+/// Explains a move that was refused, the move is dropped when closed.
+pub fn move_refusal(app: &mut ManagingApp, ctx: &egui::Context) {
+    let Some(refusal) = &app.move_selections.move_refusal else {
+        return;
+    };
+    let mut close = false;
+    egui::Window::new("Cannot load")
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.colored_label(Color32::RED, refusal);
+            close = ui.button("OK").clicked();
+        });
+    if close {
+        reset_states(app);
+    }
+}
+
+pub fn offset_edit(
+    ui: &mut egui::Ui,
+    geometry: &mut f32,
+    wear: &mut f32,
+    wear_limit: Option<f32>,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .add(egui::DragValue::new(geometry).speed(0.01).max_decimals(3))
+            .on_hover_text("Geometry")
+            .changed();
+        ui.label("+");
+        changed |= ui
+            .add(egui::DragValue::new(wear).speed(0.001).max_decimals(3))
+            .on_hover_text("Wear")
+            .changed();
+        ui.label(format!("= {:.3}", *geometry + *wear));
+        if let Some(limit) = wear_limit {
+            if wear.abs() > limit {
+                ui.colored_label(Color32::RED, "⚠")
+                    .on_hover_text(format!("Wear exceeds the limit of {:.3}", limit));
+            }
+        }
+    });
+    changed
 }
 
 pub fn edit_comment(app: &mut ManagingApp, ctx: &egui::Context) {
@@ -332,7 +623,7 @@ pub fn edit_comment(app: &mut ManagingApp, ctx: &egui::Context) {
     if magazine_index.is_none() {
         return;
     }
-    let comment = &mut magazine.contents[magazine_index.unwrap()].4.comment;
+    let comment = &mut magazine.contents[magazine_index.unwrap()].comment.comment;
     let mut edit_done = false;
     egui::Window::new("Edit comment").show(ctx, |ui| {
         ui.text_edit_multiline(comment);
@@ -345,3 +636,162 @@ pub fn edit_comment(app: &mut ManagingApp, ctx: &egui::Context) {
         reset_states(app);
     }
 }
+
+pub fn edit_slot(app: &mut ManagingApp, ctx: &egui::Context) {
+    let magazine_index = app.move_selections.selected_slot_index_magazine;
+    let machine = &mut app.machines[app.selections.machine.unwrap()];
+    let magazine = &mut machine.magazines[machine.current_magazine.unwrap()];
+    if magazine_index.is_none() {
+        return;
+    }
+    let slot = &mut magazine.contents[magazine_index.unwrap()];
+    let mut edit_done = false;
+    egui::Window::new(format!("Edit slot {}", slot.pocket)).show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("T-number:");
+            ui.add(egui::DragValue::new(&mut slot.t_number).clamp_range(0..=9999));
+        });
+        ui.horizontal(|ui| {
+            ui.label("State:");
+            if slot.is_loaded() {
+                ui.radio_value(&mut slot.state, SlotState::Occupied, "Occupied");
+            } else {
+                ui.radio_value(&mut slot.state, SlotState::Empty, "Empty");
+                ui.radio_value(&mut slot.state, SlotState::Reserved, "Reserved");
+            }
+            ui.radio_value(&mut slot.state, SlotState::Blocked, "Blocked");
+        });
+        ui.checkbox(&mut slot.locked, "Tool locked")
+            .on_hover_text("The control will not call this tool");
+        if let Some(station) = &mut slot.station {
+            station.edit(ui);
+        }
+        ui.horizontal(|ui| {
+            let mut has_sister_tool = slot.sister_tool.is_some();
+            ui.checkbox(&mut has_sister_tool, "Sister tool:");
+            match (has_sister_tool, slot.sister_tool) {
+                (true, None) => slot.sister_tool = Some(0),
+                (false, Some(_)) => slot.sister_tool = None,
+                _ => {}
+            }
+            if let Some(sister_tool) = &mut slot.sister_tool {
+                ui.label("T");
+                ui.add(egui::DragValue::new(sister_tool).clamp_range(0..=9999));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Tool life [min]:");
+            ui.add(
+                egui::DragValue::new(&mut slot.tool_life.current_time)
+                    .speed(0.1)
+                    .clamp_range(0.0..=f32::MAX),
+            )
+            .on_hover_text("Used");
+            ui.label("/");
+            ui.add(
+                egui::DragValue::new(&mut slot.tool_life.max_time)
+                    .speed(0.1)
+                    .clamp_range(0.0..=f32::MAX),
+            )
+            .on_hover_text("Maximum, 0 for no limit");
+        });
+        if ui.button("OK").clicked() {
+            edit_done = true;
+        }
+    });
+    if edit_done {
+        slot.refresh_state();
+        magazine.refresh_blocked_pockets();
+        app.display_magazine = magazine.clone();
+        reset_states(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mill::Mill;
+    use crate::offset_transfer::test_support::magazine;
+
+    fn oversized(left: usize, right: usize) -> Tool {
+        Tool::Mill(Mill {
+            name: "Face mill".to_string(),
+            oversize: Oversize { left, right },
+            ..Mill::default()
+        })
+    }
+
+    fn blocked(magazine: &Magazine) -> Vec<(usize, Option<usize>)> {
+        magazine
+            .contents
+            .iter()
+            .filter(|slot| slot.state == SlotState::Blocked)
+            .map(|slot| (slot.pocket, slot.blocked_by))
+            .collect()
+    }
+
+    #[test]
+    fn blocking_wraps_around_both_ends() {
+        let magazine = magazine("Magazine 0", 6);
+        let oversize = Oversize { left: 1, right: 1 };
+        assert_eq!(magazine.blocked_pockets(0, &oversize), [1, 5]);
+        assert_eq!(magazine.blocked_pockets(5, &oversize), [0, 4]);
+        // Never more than the other pockets, never the tool's own
+        let oversize = Oversize { left: 5, right: 5 };
+        assert_eq!(magazine.blocked_pockets(0, &oversize), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn refresh_blocks_across_the_first_and_last_pocket() {
+        let mut first = magazine("Magazine 0", 6);
+        first.contents[0].tool = Some(oversized(1, 1));
+        first.contents[0].refresh_state();
+        first.refresh_blocked_pockets();
+        assert_eq!(blocked(&first), [(1, Some(0)), (5, Some(0))]);
+        assert!(!first.contents[5].accepts_contents());
+
+        let mut last = magazine("Magazine 0", 6);
+        last.contents[5].tool = Some(oversized(0, 2));
+        last.contents[5].refresh_state();
+        last.refresh_blocked_pockets();
+        assert_eq!(blocked(&last), [(0, Some(5)), (1, Some(5))]);
+    }
+
+    #[test]
+    fn occupied_neighbours_conflict() {
+        let mut magazine = magazine("Magazine 0", 6);
+        magazine.contents[5].tool = Some(Tool::Mill(Mill::default()));
+        let tool = oversized(1, 1);
+        assert_eq!(
+            magazine.oversize_conflict(0, &tool).as_deref(),
+            Some("Face mill is oversized and needs slot 5 empty")
+        );
+        assert_eq!(magazine.oversize_conflict(2, &tool), None);
+
+        // Seen from either pocket once both are loaded
+        magazine.contents[0].tool = Some(tool);
+        assert!(magazine.oversize_conflict_at(0).is_some());
+        assert_eq!(
+            magazine.oversize_conflict_at(5).as_deref(),
+            Some("Face mill is oversized and needs slot 5 empty")
+        );
+        assert_eq!(magazine.oversize_conflict_at(3), None);
+    }
+
+    #[test]
+    fn unloading_releases_blocked_pockets() {
+        let mut magazine = magazine("Magazine 0", 6);
+        magazine.contents[0].tool = Some(oversized(1, 1));
+        magazine.contents[0].refresh_state();
+        magazine.refresh_blocked_pockets();
+
+        magazine.contents[0].tool = None;
+        magazine.contents[0].refresh_state();
+        magazine.refresh_blocked_pockets();
+        assert!(blocked(&magazine).is_empty());
+        for slot in magazine.contents.iter() {
+            assert_eq!(slot.state, SlotState::Empty);
+            assert_eq!(slot.blocked_by, None);
+        }
+    }
+}
@@ -0,0 +1,600 @@
+use std::fmt;
+
+use egui_extras::*;
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::offset_transfer::ImportReport;
+use crate::{reset_states, ManagingApp};
+
+/// ISO 513 workpiece material groups.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum IsoGroup {
+    #[default]
+    P,
+    M,
+    K,
+    N,
+    S,
+    H,
+}
+
+impl IsoGroup {
+    pub fn description(&self) -> &'static str {
+        match self {
+            IsoGroup::P => "Steel",
+            IsoGroup::M => "Stainless steel",
+            IsoGroup::K => "Cast iron",
+            IsoGroup::N => "Non-ferrous",
+            IsoGroup::S => "Heat resistant and titanium alloys",
+            IsoGroup::H => "Hardened steel",
+        }
+    }
+}
+
+impl fmt::Display for IsoGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Material of the cutting edge, which decides the usable cutting speed.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum CuttingToolMaterial {
+    Hss,
+    #[default]
+    Carbide,
+    Ceramic,
+    Cbn,
+}
+
+impl CuttingToolMaterial {
+    /// Column name prefix in material CSV files, e.g. `vc_carbide_min`.
+    pub fn csv_key(&self) -> &'static str {
+        match self {
+            CuttingToolMaterial::Hss => "hss",
+            CuttingToolMaterial::Carbide => "carbide",
+            CuttingToolMaterial::Ceramic => "ceramic",
+            CuttingToolMaterial::Cbn => "cbn",
+        }
+    }
+}
+
+impl fmt::Display for CuttingToolMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CuttingToolMaterial::Hss => write!(f, "HSS"),
+            CuttingToolMaterial::Carbide => write!(f, "Carbide"),
+            CuttingToolMaterial::Ceramic => write!(f, "Ceramic"),
+            CuttingToolMaterial::Cbn => write!(f, "CBN"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct VcRange {
+    pub tool_material: CuttingToolMaterial,
+    pub min: f32, // [m/min]
+    pub max: f32, // [m/min]
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct Material {
+    pub grade: String,
+    pub iso_group: IsoGroup,
+    pub density: f32, // [g/cm³]
+    pub kc11: f32,    // Specific cutting force at 1 mm chip thickness [N/mm²]
+    pub mc: f32,      // Chip thickness exponent
+    pub vc_ranges: Vec<VcRange>,
+}
+
+impl Material {
+    pub fn new(
+        grade: &str,
+        iso_group: IsoGroup,
+        density: f32,
+        kc11: f32,
+        mc: f32,
+        vc_ranges: &[(CuttingToolMaterial, f32, f32)],
+    ) -> Self {
+        Self {
+            grade: grade.to_string(),
+            iso_group,
+            density,
+            kc11,
+            mc,
+            vc_ranges: vc_ranges
+                .iter()
+                .map(|(tool_material, min, max)| VcRange {
+                    tool_material: *tool_material,
+                    min: *min,
+                    max: *max,
+                })
+                .collect(),
+        }
+    }
+
+    /// Specific cutting force [N/mm²] at chip thickness `h` [mm], kc = kc1.1 * h^-mc.
+    pub fn specific_cutting_force(&self, chip_thickness: f32) -> f32 {
+        if chip_thickness <= 0.0 {
+            return self.kc11;
+        }
+        self.kc11 * chip_thickness.powf(-self.mc)
+    }
+
+    /// Recommended range for a tool material, `None` when the material is not cut with it.
+    pub fn vc_range(&self, tool_material: CuttingToolMaterial) -> Option<&VcRange> {
+        self.vc_ranges
+            .iter()
+            .find(|range| range.tool_material == tool_material && range.max > 0.0)
+    }
+
+    pub fn vc_range_mut(&mut self, tool_material: CuttingToolMaterial) -> &mut VcRange {
+        let index = match self
+            .vc_ranges
+            .iter()
+            .position(|range| range.tool_material == tool_material)
+        {
+            Some(index) => index,
+            None => {
+                self.vc_ranges.push(VcRange {
+                    tool_material,
+                    min: 0.0,
+                    max: 0.0,
+                });
+                self.vc_ranges.len() - 1
+            }
+        };
+        &mut self.vc_ranges[index]
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct MaterialCatalogue {
+    pub materials: Vec<Material>,
+}
+
+impl Default for MaterialCatalogue {
+    fn default() -> Self {
+        use CuttingToolMaterial::*;
+        use IsoGroup::*;
+        Self {
+            materials: vec![
+                Material::new(
+                    "S235JR",
+                    P,
+                    7.85,
+                    1500.0,
+                    0.25,
+                    &[(Hss, 30.0, 45.0), (Carbide, 220.0, 320.0)],
+                ),
+                Material::new(
+                    "S355J2",
+                    P,
+                    7.85,
+                    1600.0,
+                    0.25,
+                    &[(Hss, 25.0, 40.0), (Carbide, 200.0, 300.0)],
+                ),
+                Material::new(
+                    "C45",
+                    P,
+                    7.85,
+                    1700.0,
+                    0.25,
+                    &[(Hss, 20.0, 35.0), (Carbide, 180.0, 280.0)],
+                ),
+                Material::new(
+                    "42CrMo4",
+                    P,
+                    7.85,
+                    1900.0,
+                    0.25,
+                    &[(Hss, 15.0, 25.0), (Carbide, 150.0, 230.0)],
+                ),
+                Material::new(
+                    "304",
+                    M,
+                    7.9,
+                    2000.0,
+                    0.21,
+                    &[(Hss, 12.0, 22.0), (Carbide, 130.0, 210.0)],
+                ),
+                Material::new(
+                    "316L",
+                    M,
+                    8.0,
+                    2100.0,
+                    0.21,
+                    &[(Hss, 10.0, 20.0), (Carbide, 120.0, 200.0)],
+                ),
+                Material::new(
+                    "EN-GJL-250",
+                    K,
+                    7.2,
+                    1150.0,
+                    0.27,
+                    &[
+                        (Hss, 20.0, 30.0),
+                        (Carbide, 180.0, 300.0),
+                        (Ceramic, 500.0, 900.0),
+                    ],
+                ),
+                Material::new(
+                    "EN-GJS-400",
+                    K,
+                    7.1,
+                    1225.0,
+                    0.25,
+                    &[
+                        (Hss, 18.0, 28.0),
+                        (Carbide, 150.0, 250.0),
+                        (Ceramic, 300.0, 600.0),
+                    ],
+                ),
+                Material::new(
+                    "6082-T6",
+                    N,
+                    2.7,
+                    800.0,
+                    0.23,
+                    &[(Hss, 100.0, 200.0), (Carbide, 300.0, 1000.0)],
+                ),
+                Material::new(
+                    "7075-T6",
+                    N,
+                    2.81,
+                    850.0,
+                    0.23,
+                    &[(Hss, 80.0, 150.0), (Carbide, 300.0, 800.0)],
+                ),
+                Material::new(
+                    "CW614N",
+                    N,
+                    8.5,
+                    700.0,
+                    0.27,
+                    &[(Hss, 60.0, 100.0), (Carbide, 200.0, 400.0)],
+                ),
+                Material::new(
+                    "Ti6Al4V",
+                    S,
+                    4.43,
+                    1700.0,
+                    0.23,
+                    &[(Hss, 5.0, 12.0), (Carbide, 40.0, 80.0)],
+                ),
+                Material::new(
+                    "Inconel 718",
+                    S,
+                    8.19,
+                    2800.0,
+                    0.25,
+                    &[
+                        (Hss, 3.0, 8.0),
+                        (Carbide, 20.0, 50.0),
+                        (Ceramic, 200.0, 350.0),
+                    ],
+                ),
+                Material::new(
+                    "Hardened steel 55 HRC",
+                    H,
+                    7.85,
+                    3500.0,
+                    0.25,
+                    &[(Carbide, 50.0, 100.0), (Cbn, 100.0, 250.0)],
+                ),
+            ],
+        }
+    }
+}
+
+impl MaterialCatalogue {
+    pub fn get(&self, grade: &str) -> Option<&Material> {
+        self.materials
+            .iter()
+            .find(|material| material.grade == grade)
+    }
+
+    /// Replaces the material with the same grade, or adds it at the end.
+    pub fn insert(&mut self, material: Material) {
+        match self
+            .materials
+            .iter_mut()
+            .find(|existing| existing.grade == material.grade)
+        {
+            Some(existing) => *existing = material,
+            None => self.materials.push(material),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct MaterialCatalogueFields {
+    pub path: String,
+    #[serde(skip)]
+    pub report: Option<ImportReport>,
+    #[serde(skip)]
+    pub status: Option<String>,
+}
+
+impl Default for MaterialCatalogueFields {
+    fn default() -> Self {
+        Self {
+            path: "materials.csv".to_string(),
+            report: None,
+            status: None,
+        }
+    }
+}
+
+/// Reads materials from CSV with a header line. `grade` and `iso_group` are required, `density`,
+/// `kc11`, `mc` and `vc_<tool material>_min`/`_max` (e.g. `vc_carbide_max`) are optional.
+/// Both `,` and `;` separated files are accepted.
+pub fn import_materials_csv(catalogue: &mut MaterialCatalogue, csv: &str) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        report.error(1, "File is empty");
+        return report;
+    };
+    let separator = if header.contains(';') { ';' } else { ',' };
+    let columns: Vec<String> = header
+        .split(separator)
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    for required in ["grade", "iso_group"] {
+        if !columns.iter().any(|column| column == required) {
+            report.error(1, format!("Header has no '{}' column", required));
+            return report;
+        }
+    }
+
+    for (i, line) in lines {
+        let line_number = i + 1;
+        let cells: Vec<&str> = line.split(separator).map(|cell| cell.trim()).collect();
+        let cell = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .and_then(|index| cells.get(index))
+                .filter(|cell| !cell.is_empty())
+                .copied()
+        };
+        let number = |name: &str| -> Result<Option<f32>, String> {
+            cell(name)
+                .map(|cell| {
+                    cell.replace(',', ".")
+                        .parse::<f32>()
+                        .map_err(|_| format!("Invalid number '{}' in column {}", cell, name))
+                })
+                .transpose()
+        };
+        let parsed = || -> Result<Material, String> {
+            let grade = cell("grade").ok_or("Missing grade")?;
+            let group = cell("iso_group").ok_or("Missing ISO group")?;
+            let iso_group = IsoGroup::iter()
+                .find(|iso_group| iso_group.to_string().eq_ignore_ascii_case(group))
+                .ok_or(format!("Unknown ISO group '{}'", group))?;
+            let mut material = Material::new(
+                grade,
+                iso_group,
+                number("density")?.unwrap_or(0.0),
+                number("kc11")?.unwrap_or(0.0),
+                number("mc")?.unwrap_or(0.0),
+                &[],
+            );
+            for tool_material in CuttingToolMaterial::iter() {
+                let key = tool_material.csv_key();
+                let min = number(&format!("vc_{}_min", key))?;
+                let max = number(&format!("vc_{}_max", key))?;
+                if min.is_some() || max.is_some() {
+                    let range = material.vc_range_mut(tool_material);
+                    range.min = min.unwrap_or(0.0);
+                    range.max = max.unwrap_or(range.min);
+                }
+            }
+            Ok(material)
+        };
+        match parsed() {
+            Ok(material) => {
+                catalogue.insert(material);
+                report.imported += 1;
+            }
+            Err(err) => report.error(line_number, err),
+        }
+    }
+    report
+}
+
+/// Combo box choosing a material by grade, shared by the calculators.
+pub fn select_material(
+    ui: &mut egui::Ui,
+    label: &str,
+    catalogue: &MaterialCatalogue,
+    grade: &mut Option<String>,
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_label(label)
+        .selected_text(grade.clone().unwrap_or("None".to_string()))
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(grade, None, "None").changed();
+            for material in catalogue.materials.iter() {
+                changed |= ui
+                    .selectable_value(
+                        grade,
+                        Some(material.grade.clone()),
+                        format!("{} ({})", material.grade, material.iso_group),
+                    )
+                    .on_hover_text(material.iso_group.description())
+                    .changed();
+            }
+        });
+    changed
+}
+
+const CSV_COLUMNS: &str =
+    "Columns: grade, iso_group, density, kc11, mc, vc_carbide_min, vc_carbide_max, ...";
+
+/// Cells of one catalogue row, returns true when Delete was clicked.
+fn material_row(row: &mut TableRow<'_, '_>, index: usize, material: &mut Material) -> bool {
+    row.col(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut material.grade).desired_width(150.0));
+    });
+    row.col(|ui| {
+        egui::ComboBox::from_id_source(("iso_group", index))
+            .selected_text(material.iso_group.to_string())
+            .show_ui(ui, |ui| {
+                for iso_group in IsoGroup::iter() {
+                    ui.selectable_value(
+                        &mut material.iso_group,
+                        iso_group,
+                        format!("{} {}", iso_group, iso_group.description()),
+                    );
+                }
+            });
+    });
+    row.col(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut material.density)
+                .speed(0.01)
+                .clamp_range(0.0..=25.0),
+        );
+    });
+    row.col(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut material.kc11)
+                .speed(10.0)
+                .clamp_range(0.0..=10000.0),
+        );
+    });
+    row.col(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut material.mc)
+                .speed(0.01)
+                .clamp_range(0.0..=1.0),
+        );
+    });
+    row.col(|_| {});
+    for tool_material in CuttingToolMaterial::iter() {
+        row.col(|ui| {
+            // Edit a copy, a range is only added to the material once a value is entered
+            let (mut min, mut max) = material
+                .vc_ranges
+                .iter()
+                .find(|range| range.tool_material == tool_material)
+                .map_or((0.0, 0.0), |range| (range.min, range.max));
+            let mut changed = ui
+                .add(egui::DragValue::new(&mut min).clamp_range(0.0..=5000.0))
+                .changed();
+            ui.label("-");
+            changed |= ui
+                .add(egui::DragValue::new(&mut max).clamp_range(0.0..=5000.0))
+                .changed();
+            if changed {
+                let range = material.vc_range_mut(tool_material);
+                range.min = min;
+                range.max = max;
+            }
+        });
+    }
+    let mut delete = false;
+    row.col(|ui| {
+        delete = ui.button("Delete").clicked();
+    });
+    delete
+}
+
+pub fn material_catalogue(app: &mut ManagingApp, ctx: &egui::Context) {
+    let mut is_window_open = true;
+    let mut import = false;
+    let mut remove = None;
+    egui::Window::new("Materials")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let fields = &mut app.gui_singletons.material_catalogue;
+            ui.horizontal(|ui| {
+                if ui.button("Add material").clicked() {
+                    app.materials.materials.push(Material::new(
+                        "New material",
+                        IsoGroup::P,
+                        7.85,
+                        1500.0,
+                        0.25,
+                        &[],
+                    ));
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.label("CSV:");
+                    ui.text_edit_singleline(&mut fields.path);
+                    import = ui.button("Import").on_hover_text(CSV_COLUMNS).clicked();
+                }
+            });
+            if let Some(status) = &fields.status {
+                ui.label(status);
+            }
+            if let Some(report) = &fields.report {
+                ui.label(format!("Imported {} materials", report.imported));
+                for error in report.errors.iter() {
+                    ui.colored_label(egui::Color32::RED, error.to_string());
+                }
+            }
+            ui.separator();
+            TableBuilder::new(ui)
+                .columns(Column::auto().resizable(true).clip(false), 6)
+                .columns(Column::auto(), CuttingToolMaterial::iter().count() + 1)
+                .max_scroll_height(500.0)
+                .header(20.0, |mut header| {
+                    for title in ["Grade", "ISO", "Density [g/cm³]", "kc1.1 [N/mm²]", "mc"] {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                    header.col(|_| {});
+                    for tool_material in CuttingToolMaterial::iter() {
+                        header.col(|ui| {
+                            ui.strong(format!("Vc {} [m/min]", tool_material));
+                        });
+                    }
+                    header.col(|_| {});
+                })
+                .body(|mut body| {
+                    for (i, material) in app.materials.materials.iter_mut().enumerate() {
+                        body.row(24.0, |mut row| {
+                            if material_row(&mut row, i, material) {
+                                remove = Some(i);
+                            }
+                        });
+                    }
+                });
+        });
+
+    if let Some(index) = remove {
+        app.materials.materials.remove(index);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if import {
+        let fields = &mut app.gui_singletons.material_catalogue;
+        match std::fs::read_to_string(&fields.path) {
+            Ok(csv) => {
+                fields.report = Some(import_materials_csv(&mut app.materials, &csv));
+                fields.status = None;
+            }
+            Err(err) => {
+                fields.report = None;
+                fields.status = Some(format!("Could not load {}: {}", fields.path, err));
+            }
+        }
+    }
+    if !is_window_open {
+        reset_states(app);
+    }
+}
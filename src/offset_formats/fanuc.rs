@@ -0,0 +1,276 @@
+use std::fmt::Write;
+
+use crate::magazine::Magazine;
+use crate::offset_transfer::*;
+use crate::slot::ToolOffsets;
+use crate::utility_calculations::*;
+
+// Memory C offset registers addressed by G10 L..
+pub const LENGTH_GEOMETRY: u32 = 10;
+pub const LENGTH_WEAR: u32 = 11;
+pub const RADIUS_GEOMETRY: u32 = 12;
+pub const RADIUS_WEAR: u32 = 13;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct FanucOptions {
+    pub program_number: u32,
+    pub incremental: bool, // G91 instead of G90
+    pub metric: bool,      // G21 instead of G20
+}
+
+impl Default for FanucOptions {
+    fn default() -> Self {
+        Self {
+            program_number: 9000,
+            incremental: false,
+            metric: true,
+        }
+    }
+}
+
+pub fn fanuc_options_edit(options: &mut FanucOptions, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.label("Program number: O");
+        ui.add(egui::DragValue::new(&mut options.program_number).clamp_range(1..=9999));
+    });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut options.incremental, false, "Absolute (G90)");
+        ui.radio_value(&mut options.incremental, true, "Incremental (G91)");
+    });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut options.metric, true, "Metric (G21)");
+        ui.radio_value(&mut options.metric, false, "Inch (G20)");
+    });
+}
+
+fn offset_register(offsets: &mut ToolOffsets, register: u32) -> Option<&mut f32> {
+    match register {
+        LENGTH_GEOMETRY => Some(&mut offsets.length_geometry),
+        LENGTH_WEAR => Some(&mut offsets.length_wear),
+        RADIUS_GEOMETRY => Some(&mut offsets.radius_geometry),
+        RADIUS_WEAR => Some(&mut offsets.radius_wear),
+        _ => None,
+    }
+}
+
+/// Fanuc comments may not contain parentheses and are conventionally upper case.
+fn fanuc_comment(text: &str) -> String {
+    text.replace(['(', ')'], "").to_uppercase()
+}
+
+/// Writes a G10 program setting the offsets of every loaded slot, addressed by T-number.
+pub fn export_fanuc_g10(magazines: &[Magazine], options: &FanucOptions) -> String {
+    let distance_mode = if options.incremental { "G91" } else { "G90" };
+    let mut program = String::new();
+    let _ = writeln!(program, "%");
+    let _ = writeln!(program, "O{:04} (TOOL OFFSETS)", options.program_number);
+    let _ = writeln!(program, "{}", if options.metric { "G21" } else { "G20" });
+    for magazine in magazines.iter() {
+        let _ = writeln!(program, "({})", fanuc_comment(&magazine.name));
+        for slot in magazine.contents.iter().filter(|slot| slot.is_loaded()) {
+            if let Some(tool) = &slot.tool {
+                let _ = writeln!(
+                    program,
+                    "(T{} {})",
+                    slot.t_number,
+                    fanuc_comment(&tool.get_name())
+                );
+            }
+            for (register, value) in [
+                (LENGTH_GEOMETRY, slot.offsets.length_geometry),
+                (LENGTH_WEAR, slot.offsets.length_wear),
+                (RADIUS_GEOMETRY, slot.offsets.radius_geometry),
+                (RADIUS_WEAR, slot.offsets.radius_wear),
+            ] {
+                let value = if options.metric {
+                    format!("{:.3}", value)
+                } else {
+                    format!("{:.4}", millimeters_to_inches(value))
+                };
+                let _ = writeln!(
+                    program,
+                    "{} G10 L{} P{} R{}",
+                    distance_mode, register, slot.t_number, value
+                );
+            }
+        }
+    }
+    let _ = writeln!(program, "M30");
+    let _ = writeln!(program, "%");
+    program
+}
+
+/// Applies a G10 program (or a punched-out offset file, which uses the same words) to the slot
+/// offsets. G20/G21 and G90/G91 are modal, starting from `options`.
+pub fn import_fanuc_g10(
+    magazines: &mut [Magazine],
+    program: &str,
+    options: &FanucOptions,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut metric = options.metric;
+    let mut incremental = options.incremental;
+    for (i, line) in program.lines().enumerate() {
+        let line_number = i + 1;
+        let words = match parse_words(line) {
+            Ok(words) => words,
+            Err(err) => {
+                report.error(line_number, err);
+                continue;
+            }
+        };
+        let mut is_g10 = false;
+        let (mut l, mut p, mut r) = (None, None, None);
+        for (address, value) in words.iter() {
+            // G10.1, G90.1 and the like are other commands, only whole G codes are matched
+            let whole = (value.fract() == 0.0).then_some(*value as u32);
+            match (address, whole) {
+                ('G', Some(10)) => is_g10 = true,
+                ('G', Some(20)) => metric = false,
+                ('G', Some(21)) => metric = true,
+                ('G', Some(90)) => incremental = false,
+                ('G', Some(91)) => incremental = true,
+                ('L', _) => l = Some(*value as u32),
+                ('P', _) => p = Some(*value as usize),
+                ('R', _) => r = Some(*value),
+                _ => {}
+            }
+        }
+        if !is_g10 {
+            continue;
+        }
+        let (Some(l), Some(p), Some(r)) = (l, p, r) else {
+            report.error(line_number, "G10 needs L, P and R words");
+            continue;
+        };
+        let r = if metric { r } else { inches_to_millimeters(r) };
+        let Some(slot) = magazines
+            .iter_mut()
+            .flat_map(|magazine| magazine.contents.iter_mut())
+            .find(|slot| slot.t_number == p)
+        else {
+            report.error(line_number, format!("No slot has T-number {}", p));
+            continue;
+        };
+        match offset_register(&mut slot.offsets, l) {
+            Some(register) => {
+                if incremental {
+                    *register += r;
+                } else {
+                    *register = r;
+                }
+                report.imported += 1;
+            }
+            None => report.error(
+                line_number,
+                format!("G10 L{} is not a tool offset (expected L10-L13)", l),
+            ),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::*;
+
+    fn offsets() -> ToolOffsets {
+        ToolOffsets {
+            length_geometry: 123.456,
+            radius_geometry: 5.0,
+            length_wear: -0.012,
+            radius_wear: 0.004,
+        }
+    }
+
+    // T2 holds a mill with `offsets()`, T1 and T3 are empty
+    fn loaded_magazine() -> Magazine {
+        let mut magazine = magazine("Magazine 0", 3);
+        magazine.contents[1].tool = mill();
+        magazine.contents[1].offsets = offsets();
+        magazine
+    }
+
+    fn assert_offsets_near(a: &ToolOffsets, b: &ToolOffsets, tolerance: f32) {
+        assert_near(a.length_geometry, b.length_geometry, tolerance);
+        assert_near(a.radius_geometry, b.radius_geometry, tolerance);
+        assert_near(a.length_wear, b.length_wear, tolerance);
+        assert_near(a.radius_wear, b.radius_wear, tolerance);
+    }
+
+    #[test]
+    fn g10_round_trip() {
+        // Inch programs carry four decimals, 0.0001" is 0.00254 mm
+        for (metric, tolerance) in [(true, 1e-4), (false, 3e-3)] {
+            let options = FanucOptions {
+                metric,
+                ..FanucOptions::default()
+            };
+            let (magazines, report) = round_trip(
+                &[loaded_magazine()],
+                |magazines| export_fanuc_g10(magazines, &options),
+                |slot| slot.offsets = ToolOffsets::default(),
+                |magazines, program| import_fanuc_g10(magazines, program, &options),
+            );
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+            assert_eq!(report.imported, 4);
+            assert_offsets_near(&magazines[0].contents[1].offsets, &offsets(), tolerance);
+        }
+    }
+
+    #[test]
+    fn g10_units_and_distance_mode_are_modal() {
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_fanuc_g10(
+            &mut magazines,
+            "G20\nG10 L10 P2 R1.0\nG91\nG10 L10 P2 R0.5\nG21 G90\nG10 L11 P2 R0.1",
+            &FanucOptions::default(),
+        );
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.imported, 3);
+        let offsets = &magazines[0].contents[1].offsets;
+        assert_near(offsets.length_geometry, 38.1, 1e-4);
+        assert_near(offsets.length_wear, 0.1, 1e-6);
+    }
+
+    #[test]
+    fn decimal_g_codes_are_not_matched() {
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_fanuc_g10(
+            &mut magazines,
+            "G10.1 L10 P2 R1.0\nG20.5 G91.1\nG10 L11 P2 R0.5",
+            &FanucOptions::default(),
+        );
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.imported, 1);
+        let slot = &magazines[0].contents[1];
+        assert_eq!(slot.offsets.length_geometry, offsets().length_geometry);
+        assert_eq!(slot.offsets.length_wear, 0.5);
+    }
+
+    #[test]
+    fn g10_incremental_adds_to_offsets() {
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_fanuc_g10(
+            &mut magazines,
+            "G91 G10 L11 P2 R-0.01",
+            &FanucOptions::default(),
+        );
+        assert_eq!(report.imported, 1);
+        assert_near(magazines[0].contents[1].offsets.length_wear, -0.022, 1e-6);
+    }
+
+    #[test]
+    fn g10_rejects_malformed_lines() {
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_fanuc_g10(
+            &mut magazines,
+            "G10 L2 P2 R1.0\nG10 L10 P2\nG10 L10 P9 R1.0\nG10 L10 P2 R1..0",
+            &FanucOptions::default(),
+        );
+        assert_eq!(report.imported, 0);
+        assert_eq!(error_lines(&report), [1, 2, 3, 4]);
+        assert_eq!(magazines[0].contents[1].offsets, offsets());
+    }
+}
@@ -0,0 +1,428 @@
+use std::fmt::Write;
+
+use crate::comment::Comment;
+use crate::magazine::Magazine;
+use crate::offset_transfer::*;
+use crate::utility_calculations::*;
+
+// Columns written on export with their fixed widths. Import reads the column positions from the
+// header line instead, so tables with more or reordered columns still load.
+const COLUMNS: [(&str, usize); 14] = [
+    ("T", 6),
+    ("NAME", 33),
+    ("L", 12),
+    ("R", 12),
+    ("R2", 12),
+    ("DL", 9),
+    ("DR", 9),
+    ("DR2", 9),
+    ("TL", 3),
+    ("RT", 6),
+    ("TIME1", 6),
+    ("TIME2", 6),
+    ("CUR_TIME", 9),
+    ("DOC", 33),
+];
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct HeidenhainOptions {
+    pub metric: bool, // BEGIN TOOL .T MM or INCH
+}
+
+impl Default for HeidenhainOptions {
+    fn default() -> Self {
+        Self { metric: true }
+    }
+}
+
+pub fn heidenhain_options_edit(options: &mut HeidenhainOptions, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut options.metric, true, "MM");
+        ui.radio_value(&mut options.metric, false, "INCH");
+    });
+}
+
+fn heidenhain_number(value: f32, metric: bool) -> String {
+    if metric {
+        format!("{:+.3}", value)
+    } else {
+        format!("{:+.4}", millimeters_to_inches(value))
+    }
+}
+
+fn push_row(table: &mut String, cells: &[String]) {
+    let mut row = String::new();
+    for (cell, (_, width)) in cells.iter().zip(COLUMNS.iter()) {
+        let cell: String = cell.chars().take(width - 1).collect();
+        let _ = write!(row, "{:<width$}", cell, width = width);
+    }
+    let _ = writeln!(table, "{}", row.trim_end());
+}
+
+/// Writes a TNC `TOOL.T` table with one row per slot holding a tool. R is half the tool
+/// diameter, DL/DR the wear and RT the sister tool.
+pub fn export_heidenhain_tool_table(magazines: &[Magazine], options: &HeidenhainOptions) -> String {
+    let number = |value: f32| heidenhain_number(value, options.metric);
+    let mut table = String::new();
+    let _ = writeln!(
+        table,
+        "BEGIN TOOL .T {}",
+        if options.metric { "MM" } else { "INCH" }
+    );
+    push_row(
+        &mut table,
+        &COLUMNS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>(),
+    );
+    for slot in magazines
+        .iter()
+        .flat_map(|magazine| magazine.contents.iter())
+    {
+        let Some(tool) = &slot.tool else {
+            continue;
+        };
+        let doc = if slot.comment.comment == Comment::default().comment {
+            String::new()
+        } else {
+            slot.comment.comment.replace(['\n', '\r'], " ")
+        };
+        push_row(
+            &mut table,
+            &[
+                slot.t_number.to_string(),
+                tool.get_name(),
+                number(slot.offsets.length_geometry),
+                number(tool.get_diameter() * 0.5),
+                number(0.0),
+                number(slot.offsets.length_wear),
+                number(slot.offsets.radius_wear),
+                number(0.0),
+                if slot.locked { "L" } else { "" }.to_string(),
+                slot.sister_tool
+                    .map(|t_number| t_number.to_string())
+                    .unwrap_or_default(),
+                format!("{:.0}", slot.tool_life.max_time),
+                "0".to_string(),
+                format!("{:.1}", slot.tool_life.current_time),
+                doc,
+            ],
+        );
+    }
+    let _ = writeln!(table, "[END]");
+    table
+}
+
+/// One tool row of a `TOOL.T` table, lengths already in millimeters. Fields are `None` when the
+/// cell, or for RT, TIME1, CUR_TIME and TL the whole column, is missing.
+struct HeidenhainRow {
+    t_number: usize,
+    name: Option<String>,
+    l: Option<f32>,
+    r: Option<f32>,
+    dl: Option<f32>,
+    dr: Option<f32>,
+    locked: Option<bool>,
+    rt: Option<Option<usize>>, // Some(None) clears the sister tool
+    time1: Option<f32>,
+    cur_time: Option<f32>,
+    doc: Option<String>,
+}
+
+fn parse_row(
+    line: &str,
+    columns: &[(String, usize, usize)],
+    metric: bool,
+) -> Result<HeidenhainRow, String> {
+    let has_column = |name: &str| columns.iter().any(|(column, ..)| column == name);
+    let cell = |name: &str| {
+        columns
+            .iter()
+            .find(|(column, ..)| column == name)
+            .map(|(_, start, end)| {
+                line.chars()
+                    .skip(*start)
+                    .take(end - start)
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .filter(|cell| !cell.is_empty())
+    };
+    let parsed = |name: &str| -> Result<Option<f32>, String> {
+        cell(name)
+            .map(|cell| {
+                cell.parse::<f32>()
+                    .map_err(|_| format!("Invalid number '{}' in column {}", cell, name))
+            })
+            .transpose()
+    };
+    // An empty cell in a column that exists clears the value, an absent column keeps it
+    let column_value = |name: &str| -> Result<Option<Option<f32>>, String> {
+        has_column(name).then(|| parsed(name)).transpose()
+    };
+    let length = |name: &str| -> Result<Option<f32>, String> {
+        Ok(parsed(name)?.map(|value| {
+            if metric {
+                value
+            } else {
+                inches_to_millimeters(value)
+            }
+        }))
+    };
+
+    let t_number = cell("T").ok_or("Row has no T number")?;
+    Ok(HeidenhainRow {
+        t_number: t_number
+            .parse()
+            .map_err(|_| format!("Invalid T number '{}'", t_number))?,
+        name: cell("NAME"),
+        l: length("L")?,
+        r: length("R")?,
+        dl: length("DL")?,
+        dr: length("DR")?,
+        locked: has_column("TL").then(|| cell("TL").is_some_and(|tl| tl == "L")),
+        rt: column_value("RT")?.map(|rt| rt.map(|rt| rt as usize)),
+        time1: column_value("TIME1")?.map(|time| time.unwrap_or(0.0)),
+        cur_time: column_value("CUR_TIME")?.map(|time| time.unwrap_or(0.0)),
+        doc: cell("DOC"),
+    })
+}
+
+/// Reads a TNC `TOOL.T` table into the slots with matching T-numbers.
+pub fn import_heidenhain_tool_table(
+    magazines: &mut [Magazine],
+    table: &str,
+    options: &HeidenhainOptions,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut metric = options.metric;
+    let mut columns: Option<Vec<(String, usize, usize)>> = None;
+    for (i, line) in table.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("[END]") {
+            continue;
+        }
+        if trimmed.starts_with("BEGIN") {
+            metric = !trimmed.ends_with("INCH");
+            continue;
+        }
+        if columns.is_none() {
+            if trimmed.starts_with("T ") {
+                columns = Some(header_columns(line));
+            } else {
+                report.error(line_number, "Expected the column header line before tools");
+            }
+            continue;
+        }
+        let row = match parse_row(line, columns.as_deref().unwrap_or_default(), metric) {
+            Ok(row) => row,
+            Err(err) => {
+                report.error(line_number, err);
+                continue;
+            }
+        };
+        let Some(slot) = magazines
+            .iter_mut()
+            .flat_map(|magazine| magazine.contents.iter_mut())
+            .find(|slot| slot.t_number == row.t_number)
+        else {
+            // T 0 is the null tool every TOOL.T starts with
+            if row.t_number != 0 {
+                report.error(
+                    line_number,
+                    format!("No slot has T-number {}", row.t_number),
+                );
+            }
+            continue;
+        };
+        if let Some(tool) = &mut slot.tool {
+            if let Some(name) = row.name {
+                tool.set_name(name);
+            }
+            if let Some(r) = row.r {
+                tool.set_diameter(r * 2.0);
+            }
+        }
+        if let Some(l) = row.l {
+            slot.offsets.length_geometry = l;
+        }
+        if let Some(dl) = row.dl {
+            slot.offsets.length_wear = dl;
+        }
+        if let Some(dr) = row.dr {
+            slot.offsets.radius_wear = dr;
+        }
+        if let Some(rt) = row.rt {
+            slot.sister_tool = rt;
+        }
+        if let Some(time1) = row.time1 {
+            slot.tool_life.max_time = time1;
+        }
+        if let Some(cur_time) = row.cur_time {
+            slot.tool_life.current_time = cur_time;
+        }
+        if let Some(doc) = row.doc {
+            slot.comment.comment = doc;
+        }
+        if let Some(locked) = row.locked {
+            slot.locked = locked;
+        }
+        report.imported += 1;
+    }
+    report
+}
+
+/// Column name, first and one-past-last character of every column in a header line. A column
+/// runs from the start of its name to the start of the next one.
+fn header_columns(header: &str) -> Vec<(String, usize, usize)> {
+    let chars: Vec<char> = header.chars().collect();
+    let mut starts = Vec::new();
+    for (i, c) in chars.iter().enumerate() {
+        if !c.is_whitespace() && (i == 0 || chars[i - 1].is_whitespace()) {
+            let name: String = chars[i..]
+                .iter()
+                .take_while(|c| !c.is_whitespace())
+                .collect();
+            starts.push((name, i));
+        }
+    }
+    let mut columns = Vec::new();
+    for (index, (name, start)) in starts.iter().enumerate() {
+        let end = starts
+            .get(index + 1)
+            .map(|(_, next)| *next)
+            .unwrap_or(usize::MAX);
+        columns.push((name.clone(), *start, end));
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::{ToolLife, ToolOffsets};
+    use test_support::*;
+
+    // T4 is a locked 50 mm face mill with offsets, sister tool, tool life and a comment, T9 a plain
+    // mill
+    fn loaded_magazine() -> Magazine {
+        let mut magazine = magazine("Magazine 0", 3);
+        let slot = &mut magazine.contents[0];
+        slot.tool = mill();
+        if let Some(tool) = &mut slot.tool {
+            tool.set_name("Face mill 50".to_string());
+            tool.set_diameter(50.0);
+        }
+        slot.t_number = 4;
+        slot.offsets.length_geometry = 123.456;
+        slot.offsets.length_wear = -0.012;
+        slot.offsets.radius_wear = 0.004;
+        slot.locked = true;
+        slot.sister_tool = Some(9);
+        slot.tool_life.max_time = 60.0;
+        slot.tool_life.current_time = 12.5;
+        slot.comment.comment = "Roughing only".to_string();
+        magazine.contents[2].tool = mill();
+        magazine.contents[2].t_number = 9;
+        magazine
+    }
+
+    #[test]
+    fn tool_table_round_trip() {
+        for metric in [true, false] {
+            let options = HeidenhainOptions { metric };
+            let exported = loaded_magazine();
+            let (magazines, report) = round_trip(
+                std::slice::from_ref(&exported),
+                |magazines| export_heidenhain_tool_table(magazines, &options),
+                |slot| {
+                    slot.offsets = ToolOffsets::default();
+                    slot.comment = Comment::default();
+                    slot.locked = false;
+                    slot.sister_tool = None;
+                    slot.tool_life = ToolLife::default();
+                    if let Some(tool) = &mut slot.tool {
+                        tool.set_name("Renamed".to_string());
+                        tool.set_diameter(1.0);
+                    }
+                },
+                |magazines, table| import_heidenhain_tool_table(magazines, table, &options),
+            );
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+            assert_eq!(report.imported, 2);
+            for (a, b) in exported.contents.iter().zip(magazines[0].contents.iter()) {
+                let (Some(tool), Some(loaded)) = (&a.tool, &b.tool) else {
+                    continue;
+                };
+                assert_eq!(tool.get_name(), loaded.get_name());
+                assert_near(tool.get_diameter(), loaded.get_diameter(), 3e-3);
+                assert_near(a.offsets.length_geometry, b.offsets.length_geometry, 3e-3);
+                assert_near(a.offsets.length_wear, b.offsets.length_wear, 3e-3);
+                assert_near(a.offsets.radius_wear, b.offsets.radius_wear, 3e-3);
+                assert_eq!(a.locked, b.locked);
+                assert_eq!(a.sister_tool, b.sister_tool);
+                assert_eq!(a.tool_life, b.tool_life);
+                assert_eq!(a.comment.comment, b.comment.comment);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_columns_keep_slot_values() {
+        let table = format!(
+            "BEGIN TOOL .T MM\n{:<6}{:<33}{}\n{:<6}{:<33}{}\n[END]",
+            "T", "NAME", "L", "4", "Face mill 50", "+150.000"
+        );
+        let mut magazines = vec![loaded_magazine()];
+        let report =
+            import_heidenhain_tool_table(&mut magazines, &table, &HeidenhainOptions::default());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.imported, 1);
+        let (slot, before) = (&magazines[0].contents[0], &loaded_magazine().contents[0]);
+        assert_near(slot.offsets.length_geometry, 150.0, 1e-6);
+        assert_eq!(slot.offsets.length_wear, before.offsets.length_wear);
+        assert_eq!(slot.offsets.radius_wear, before.offsets.radius_wear);
+        assert_eq!(slot.tool.as_ref().unwrap().get_diameter(), 50.0);
+        assert_eq!(slot.comment.comment, before.comment.comment);
+        assert_eq!(slot.sister_tool, before.sister_tool);
+        assert_eq!(slot.tool_life, before.tool_life);
+        assert!(slot.locked);
+    }
+
+    #[test]
+    fn empty_cells_clear_sister_tool_life_and_lock() {
+        let table = format!(
+            "BEGIN TOOL .T MM\n{:<6}{:<3}{:<6}{:<6}{}\n4\n[END]",
+            "T", "TL", "RT", "TIME1", "CUR_TIME"
+        );
+        let mut magazines = vec![loaded_magazine()];
+        let report =
+            import_heidenhain_tool_table(&mut magazines, &table, &HeidenhainOptions::default());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        let slot = &magazines[0].contents[0];
+        assert!(!slot.locked);
+        assert_eq!(slot.sister_tool, None);
+        assert_eq!(slot.tool_life, ToolLife::default());
+    }
+
+    #[test]
+    fn tool_table_rejects_malformed_rows() {
+        let options = HeidenhainOptions::default();
+        let table = export_heidenhain_tool_table(&[loaded_magazine()], &options);
+        let mut lines: Vec<String> = table.lines().map(str::to_string).collect();
+        // Same width as the number it replaces, so the row's columns stay aligned
+        lines[2] = lines[2].replacen("+123.456", "+12x.456", 1);
+        lines.insert(1, "4     Stray row".to_string());
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_heidenhain_tool_table(&mut magazines, &lines.join("\n"), &options);
+        assert_eq!(error_lines(&report), [2, 4]);
+        assert!(report.errors[1].message.contains("+12x.456"));
+        assert_eq!(report.imported, 1);
+        let (slot, before) = (&magazines[0].contents[0], &loaded_magazine().contents[0]);
+        assert_eq!(slot.offsets, before.offsets);
+        assert_eq!(slot.tool_life, before.tool_life);
+    }
+}
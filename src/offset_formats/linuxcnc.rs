@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use crate::comment::Comment;
+use crate::magazine::Magazine;
+use crate::offset_transfer::*;
+use crate::slot::Slot;
+use crate::utility_calculations::*;
+
+// Words LinuxCNC allows in a tool table entry besides T, P, Z and D. They are accepted on import
+// but not stored.
+const IGNORED_WORDS: [char; 11] = ['X', 'Y', 'A', 'B', 'C', 'U', 'V', 'W', 'I', 'J', 'Q'];
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LinuxCncOptions {
+    pub metric: bool, // Machine units of the tool table
+}
+
+impl Default for LinuxCncOptions {
+    fn default() -> Self {
+        Self { metric: true }
+    }
+}
+
+pub fn linuxcnc_options_edit(options: &mut LinuxCncOptions, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut options.metric, true, "Metric");
+        ui.radio_value(&mut options.metric, false, "Inch");
+    });
+}
+
+/// LinuxCNC pockets start at 1 (pocket 0 is the spindle) and run on across all exported
+/// magazines, so slots are numbered by their position in that sequence.
+fn slots_by_pocket(magazines: &mut [Magazine]) -> impl Iterator<Item = (usize, &mut Slot)> {
+    magazines
+        .iter_mut()
+        .flat_map(|magazine| magazine.contents.iter_mut())
+        .enumerate()
+        .map(|(i, slot)| (i + 1, slot))
+}
+
+pub fn export_linuxcnc_tool_table(magazines: &[Magazine], options: &LinuxCncOptions) -> String {
+    let to_units = |value: f32| {
+        if options.metric {
+            format!("{:.3}", value)
+        } else {
+            format!("{:.4}", millimeters_to_inches(value))
+        }
+    };
+    let mut table = String::new();
+    let slots = magazines
+        .iter()
+        .flat_map(|magazine| magazine.contents.iter())
+        .enumerate();
+    for (i, slot) in slots {
+        let Some(tool) = &slot.tool else {
+            continue;
+        };
+        let _ = write!(
+            table,
+            "T{} P{} Z{} D{}",
+            slot.t_number,
+            i + 1,
+            to_units(slot.offsets.length_total()),
+            to_units(tool.get_diameter())
+        );
+        let comment = slot.comment.comment.replace(['\n', '\r'], " ");
+        if comment.trim().is_empty() || comment == Comment::default().comment {
+            let _ = writeln!(table, " ;{}", tool.get_name());
+        } else {
+            let _ = writeln!(table, " ;{}", comment);
+        }
+    }
+    table
+}
+
+/// Applies a tool table to the slots. T sets the slot's T-number, Z the length offset (wear is
+/// kept, geometry absorbs the difference), D the diameter of the loaded tool and the text after
+/// `;` the slot comment.
+pub fn import_linuxcnc_tool_table(
+    magazines: &mut [Magazine],
+    table: &str,
+    options: &LinuxCncOptions,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let from_units = |value: f32| {
+        if options.metric {
+            value
+        } else {
+            inches_to_millimeters(value)
+        }
+    };
+    for (i, line) in table.lines().enumerate() {
+        let line_number = i + 1;
+        let (data, comment) = match line.split_once(';') {
+            Some((data, comment)) => (data, Some(comment.trim())),
+            None => (line, None),
+        };
+        if data.trim().is_empty() {
+            continue;
+        }
+        let words = match parse_words(data) {
+            Ok(words) => words,
+            Err(err) => {
+                report.error(line_number, err);
+                continue;
+            }
+        };
+        let (mut t, mut p, mut z, mut d) = (None, None, None, None);
+        let mut unknown = None;
+        for (address, value) in words {
+            match address {
+                'T' => t = Some(value as usize),
+                'P' => p = Some(value as usize),
+                'Z' => z = Some(from_units(value)),
+                'D' => d = Some(from_units(value)),
+                address if IGNORED_WORDS.contains(&address) => {}
+                address => unknown = Some(address),
+            }
+        }
+        if let Some(address) = unknown {
+            report.error(line_number, format!("Unknown word '{}'", address));
+            continue;
+        }
+        let (Some(t), Some(p)) = (t, p) else {
+            report.error(line_number, "Entry needs both a T and a P word");
+            continue;
+        };
+        let Some((_, slot)) = slots_by_pocket(magazines).find(|(pocket, _)| *pocket == p) else {
+            report.error(line_number, format!("No pocket P{} in the magazine", p));
+            continue;
+        };
+        slot.t_number = t;
+        if let Some(z) = z {
+            slot.offsets.length_geometry = z - slot.offsets.length_wear;
+        }
+        if let (Some(d), Some(tool)) = (d, &mut slot.tool) {
+            tool.set_diameter(d);
+        }
+        // Export writes the tool name when the slot has no comment of its own
+        let tool_name = slot.tool.as_ref().map(|tool| tool.get_name());
+        if let Some(comment) =
+            comment.filter(|comment| !comment.is_empty() && Some(comment.to_string()) != tool_name)
+        {
+            slot.comment.comment = comment.to_string();
+        }
+        report.imported += 1;
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::ToolOffsets;
+    use test_support::*;
+
+    // T7 in pocket 1 is a 12.5 mm mill with its own length offset, pocket 3 a mill with a comment
+    fn loaded_magazine() -> Magazine {
+        let mut magazine = magazine("Magazine 0", 3);
+        let slot = &mut magazine.contents[0];
+        slot.tool = mill();
+        if let Some(tool) = &mut slot.tool {
+            tool.set_diameter(12.5);
+        }
+        slot.t_number = 7;
+        slot.offsets.length_geometry = 98.765;
+        slot.offsets.length_wear = 0.02;
+        magazine.contents[2].tool = mill();
+        magazine.contents[2].comment.comment = "Roughing".to_string();
+        magazine
+    }
+
+    #[test]
+    fn tool_table_round_trip() {
+        for metric in [true, false] {
+            let options = LinuxCncOptions { metric };
+            let exported = loaded_magazine();
+            let (magazines, report) = round_trip(
+                std::slice::from_ref(&exported),
+                |magazines| export_linuxcnc_tool_table(magazines, &options),
+                |slot| {
+                    slot.t_number = 0;
+                    slot.offsets = ToolOffsets::default();
+                    slot.comment = Comment::default();
+                    if let Some(tool) = &mut slot.tool {
+                        tool.set_diameter(1.0);
+                    }
+                },
+                |magazines, table| import_linuxcnc_tool_table(magazines, table, &options),
+            );
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+            assert_eq!(report.imported, 2);
+            for (a, b) in exported.contents.iter().zip(magazines[0].contents.iter()) {
+                let (Some(tool), Some(loaded)) = (&a.tool, &b.tool) else {
+                    continue;
+                };
+                assert_eq!(a.t_number, b.t_number);
+                assert_eq!(a.comment.comment, b.comment.comment);
+                assert_near(tool.get_diameter(), loaded.get_diameter(), 3e-3);
+                assert_near(a.offsets.length_total(), b.offsets.length_total(), 3e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn pockets_run_on_across_magazines() {
+        let mut magazines = vec![magazine("Magazine 0", 2), magazine("Magazine 1", 2)];
+        magazines[1].contents[1].tool = mill();
+        let table = export_linuxcnc_tool_table(&magazines, &LinuxCncOptions::default());
+        assert!(table.starts_with("T2 P4 "), "{}", table);
+
+        let report = import_linuxcnc_tool_table(
+            &mut magazines,
+            "T9 P3 Z50.0\nT8 P4 Z60.0",
+            &LinuxCncOptions::default(),
+        );
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(magazines[0].contents[0].t_number, 1);
+        assert_eq!(magazines[1].contents[0].t_number, 9);
+        assert_eq!(magazines[1].contents[1].t_number, 8);
+        assert_near(magazines[1].contents[1].offsets.length_geometry, 60.0, 1e-6);
+    }
+
+    #[test]
+    fn tool_table_rejects_malformed_lines() {
+        let mut magazines = vec![loaded_magazine()];
+        let report = import_linuxcnc_tool_table(
+            &mut magazines,
+            "T1 P1 Z1 K5\nT1 Z2\nT1 P99\nT1 P1 Zx ;comment",
+            &LinuxCncOptions::default(),
+        );
+        assert_eq!(report.imported, 0);
+        assert_eq!(error_lines(&report), [1, 2, 3, 4]);
+        let slot = &magazines[0].contents[0];
+        assert_eq!(slot.t_number, 7);
+        assert_eq!(slot.offsets, loaded_magazine().contents[0].offsets);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod fanuc;
+pub mod heidenhain;
+pub mod linuxcnc;
+pub mod offset_transfer;
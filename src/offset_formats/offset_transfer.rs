@@ -0,0 +1,321 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::fanuc::*;
+use crate::heidenhain::*;
+use crate::linuxcnc::*;
+use crate::machine::selected_machine_type;
+use crate::magazine::Magazine;
+use crate::{reset_states, ManagingApp};
+
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum OffsetFormat {
+    #[default]
+    FanucG10,
+    LinuxCncToolTable,
+    HeidenhainToolTable,
+}
+
+impl fmt::Display for OffsetFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffsetFormat::FanucG10 => write!(f, "Fanuc G10 (.nc)"),
+            OffsetFormat::LinuxCncToolTable => write!(f, "LinuxCNC tool table (tool.tbl)"),
+            OffsetFormat::HeidenhainToolTable => write!(f, "Heidenhain tool table (TOOL.T)"),
+        }
+    }
+}
+
+/// A line of an imported file that could not be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    pub line: usize, // 1-based line number in the imported text
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub errors: Vec<LineError>,
+}
+
+impl ImportReport {
+    pub fn error(&mut self, line: usize, message: impl Into<String>) {
+        self.errors.push(LineError {
+            line,
+            message: message.into(),
+        });
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct OffsetTransferFields {
+    pub format: OffsetFormat,
+    pub all_magazines: bool,
+    pub fanuc: FanucOptions,
+    #[serde(default)]
+    pub linuxcnc: LinuxCncOptions,
+    #[serde(default)]
+    pub heidenhain: HeidenhainOptions,
+    pub path: String,
+    pub text: String,
+    #[serde(skip)]
+    pub status: Option<String>,
+    #[serde(skip)]
+    pub report: Option<ImportReport>,
+}
+
+impl Default for OffsetTransferFields {
+    fn default() -> Self {
+        Self {
+            format: OffsetFormat::FanucG10,
+            all_magazines: false,
+            fanuc: FanucOptions::default(),
+            linuxcnc: LinuxCncOptions::default(),
+            heidenhain: HeidenhainOptions::default(),
+            path: "offsets.nc".to_string(),
+            text: String::new(),
+            status: None,
+            report: None,
+        }
+    }
+}
+
+/// Splits an NC line into address words such as `('G', 10.0)`. Comments in parentheses and
+/// everything after `;` are dropped.
+pub fn parse_words(line: &str) -> Result<Vec<(char, f32)>, String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    let mut in_comment = false;
+    while let Some(c) = chars.next() {
+        if in_comment {
+            in_comment = c != ')';
+            continue;
+        }
+        match c {
+            '(' => in_comment = true,
+            ';' => break,
+            '%' => {}
+            c if c.is_whitespace() => {}
+            c if c.is_ascii_alphabetic() => {
+                let mut number = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii_digit() || next == '.' || next == '-' || next == '+' {
+                        number.push(next);
+                        chars.next();
+                    } else if next == ' ' && number.is_empty() {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let value = number
+                    .parse::<f32>()
+                    .map_err(|_| format!("Missing or invalid value after '{}'", c))?;
+                words.push((c.to_ascii_uppercase(), value));
+            }
+            c => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(words)
+}
+
+/// Returns the magazines an export or import should cover.
+fn target_magazines(app: &mut ManagingApp, all_magazines: bool) -> Option<&mut [Magazine]> {
+    let machine = app.machines.get_mut(app.selections.machine?)?;
+    if all_magazines {
+        return Some(&mut machine.magazines);
+    }
+    let index = machine.current_magazine?;
+    machine.magazines.get_mut(index..=index)
+}
+
+pub fn offset_transfer(app: &mut ManagingApp, ctx: &egui::Context) {
+    let mut is_window_open = true;
+    let mut export = false;
+    let mut import = false;
+    egui::Window::new("Offset transfer")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let machine_type = selected_machine_type(app);
+            let offered = |format: &OffsetFormat| {
+                machine_type.map_or(true, |machine_type| {
+                    machine_type.offers_offset_format(*format)
+                })
+            };
+            let fields = &mut app.gui_singletons.offset_transfer;
+            if !offered(&fields.format) {
+                fields.format = OffsetFormat::default();
+            }
+            egui::ComboBox::from_label("Format")
+                .selected_text(fields.format.to_string())
+                .show_ui(ui, |ui| {
+                    for format in OffsetFormat::iter().filter(offered) {
+                        ui.selectable_value(&mut fields.format, format, format.to_string());
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut fields.all_magazines, false, "Current magazine");
+                ui.radio_value(&mut fields.all_magazines, true, "All magazines");
+            });
+            match fields.format {
+                OffsetFormat::FanucG10 => fanuc_options_edit(&mut fields.fanuc, ui),
+                OffsetFormat::LinuxCncToolTable => linuxcnc_options_edit(&mut fields.linuxcnc, ui),
+                OffsetFormat::HeidenhainToolTable => {
+                    heidenhain_options_edit(&mut fields.heidenhain, ui)
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                export = ui.button("Export").clicked();
+                import = ui
+                    .button("Import")
+                    .on_hover_text("Apply the text below to the slot offsets")
+                    .clicked();
+                if ui.button("Copy to clipboard").clicked() {
+                    ui.output_mut(|output| output.copied_text = fields.text.clone());
+                }
+            });
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut fields.path);
+                if ui.button("Save").clicked() {
+                    fields.status = Some(match std::fs::write(&fields.path, &fields.text) {
+                        Ok(()) => format!("Saved {}", fields.path),
+                        Err(err) => format!("Could not save {}: {}", fields.path, err),
+                    });
+                }
+                if ui.button("Load").clicked() {
+                    fields.status = Some(match std::fs::read_to_string(&fields.path) {
+                        Ok(text) => {
+                            fields.text = text;
+                            format!("Loaded {}", fields.path)
+                        }
+                        Err(err) => format!("Could not load {}: {}", fields.path, err),
+                    });
+                }
+            });
+            if let Some(status) = &fields.status {
+                ui.label(status);
+            }
+            if let Some(report) = &fields.report {
+                ui.label(format!("Imported {} entries", report.imported));
+                for error in report.errors.iter() {
+                    ui.colored_label(egui::Color32::RED, error.to_string());
+                }
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut fields.text)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+
+    let all_magazines = app.gui_singletons.offset_transfer.all_magazines;
+    let format = app.gui_singletons.offset_transfer.format;
+    if export {
+        let options = app.gui_singletons.offset_transfer.clone();
+        let text = match target_magazines(app, all_magazines) {
+            Some(magazines) => match format {
+                OffsetFormat::FanucG10 => export_fanuc_g10(magazines, &options.fanuc),
+                OffsetFormat::LinuxCncToolTable => {
+                    export_linuxcnc_tool_table(magazines, &options.linuxcnc)
+                }
+                OffsetFormat::HeidenhainToolTable => {
+                    export_heidenhain_tool_table(magazines, &options.heidenhain)
+                }
+            },
+            None => String::new(),
+        };
+        app.gui_singletons.offset_transfer.text = text;
+        app.gui_singletons.offset_transfer.report = None;
+    }
+    if import {
+        let options = app.gui_singletons.offset_transfer.clone();
+        let report = target_magazines(app, all_magazines).map(|magazines| match format {
+            OffsetFormat::FanucG10 => import_fanuc_g10(magazines, &options.text, &options.fanuc),
+            OffsetFormat::LinuxCncToolTable => {
+                import_linuxcnc_tool_table(magazines, &options.text, &options.linuxcnc)
+            }
+            OffsetFormat::HeidenhainToolTable => {
+                import_heidenhain_tool_table(magazines, &options.text, &options.heidenhain)
+            }
+        });
+        app.gui_singletons.offset_transfer.report = report;
+        refresh_display_magazine(app);
+    }
+    if !is_window_open {
+        reset_states(app);
+    }
+}
+
+fn refresh_display_magazine(app: &mut ManagingApp) {
+    if let Some(machine) = app.selections.machine.map(|index| &app.machines[index]) {
+        if let Some(magazine) = machine.current_magazine {
+            app.display_magazine = machine.magazines[magazine].clone();
+        }
+    }
+}
+
+/// Fixtures shared by the tests of the individual formats.
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+    use crate::mill::Mill;
+    use crate::slot::Slot;
+    use crate::tool::Tool;
+    use crate::turret::MagazineKind;
+
+    /// A magazine of `size` empty pockets with T-numbers 1 and up.
+    pub fn magazine(name: &str, size: usize) -> Magazine {
+        Magazine {
+            name: name.to_string(),
+            contents: (0..size).map(Slot::new).collect(),
+            kind: MagazineKind::default(),
+        }
+    }
+
+    pub fn mill() -> Option<Tool> {
+        Some(Tool::Mill(Mill::default()))
+    }
+
+    /// Exports `magazines`, clears every slot of a copy with `clear` and imports the text into
+    /// the copy.
+    pub fn round_trip(
+        magazines: &[Magazine],
+        export: impl Fn(&[Magazine]) -> String,
+        clear: impl Fn(&mut Slot),
+        import: impl Fn(&mut [Magazine], &str) -> ImportReport,
+    ) -> (Vec<Magazine>, ImportReport) {
+        let text = export(magazines);
+        let mut imported = magazines.to_vec();
+        imported
+            .iter_mut()
+            .flat_map(|magazine| magazine.contents.iter_mut())
+            .for_each(clear);
+        let report = import(&mut imported, &text);
+        (imported, report)
+    }
+
+    pub fn error_lines(report: &ImportReport) -> Vec<usize> {
+        report.errors.iter().map(|error| error.line).collect()
+    }
+
+    pub fn assert_near(a: f32, b: f32, tolerance: f32) {
+        assert!((a - b).abs() <= tolerance, "{} != {}", a, b);
+    }
+}
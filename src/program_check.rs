@@ -0,0 +1,536 @@
+use std::fmt;
+
+use egui::Color32;
+use egui_extras::*;
+
+use crate::machine::{selected_machine_type, MachineType};
+use crate::magazine::Magazine;
+use crate::{reset_states, ManagingApp};
+
+// Loaded and programmed diameters closer than this are considered equal
+pub const DIAMETER_TOLERANCE: f32 = 0.01;
+
+/// How a program refers to a tool: by T-number, or by name in a Heidenhain `TOOL CALL "NAME"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolReference {
+    Number(usize),
+    Name(String),
+}
+
+impl fmt::Display for ToolReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolReference::Number(t_number) => write!(f, "T{}", t_number),
+            ToolReference::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequirementStatus {
+    Loaded,
+    Missing,
+    DiameterMismatch,
+}
+
+impl fmt::Display for RequirementStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequirementStatus::Loaded => write!(f, "OK"),
+            RequirementStatus::Missing => write!(f, "Missing"),
+            RequirementStatus::DiameterMismatch => write!(f, "Diameter mismatch"),
+        }
+    }
+}
+
+/// A tool the program calls and where, if anywhere, it sits in the machine.
+#[derive(Debug, Clone)]
+pub struct ToolRequirement {
+    pub tool: ToolReference,
+    pub first_line: usize,
+    pub calls: usize,
+    pub tool_changes: usize,            // Calls followed by M6, or TOOL CALL
+    pub expected_diameter: Option<f32>, // From a `(T5 D10 ...)` header comment
+    pub magazine: Option<String>,
+    pub slot: Option<usize>,
+    pub tool_name: Option<String>,
+    pub loaded_diameter: Option<f32>,
+}
+
+impl ToolRequirement {
+    pub fn status(&self) -> RequirementStatus {
+        match (self.tool_name.as_ref(), self.expected_diameter) {
+            (None, _) => RequirementStatus::Missing,
+            (Some(_), Some(expected)) => match self.loaded_diameter {
+                Some(loaded) if (loaded - expected).abs() > DIAMETER_TOLERANCE => {
+                    RequirementStatus::DiameterMismatch
+                }
+                _ => RequirementStatus::Loaded,
+            },
+            (Some(_), None) => RequirementStatus::Loaded,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ProgramCheckFields {
+    pub path: String,
+    pub program: String,
+    #[serde(default)]
+    pub t_word_offset: bool, // T0101 is tool 1 with offset 1, always on for lathes
+    #[serde(skip)]
+    pub status: Option<String>,
+    #[serde(skip)]
+    pub requirements: Vec<ToolRequirement>,
+}
+
+impl Default for ProgramCheckFields {
+    fn default() -> Self {
+        Self {
+            path: "program.nc".to_string(),
+            program: String::new(),
+            t_word_offset: false,
+            status: None,
+            requirements: Vec::new(),
+        }
+    }
+}
+
+/// Number following position `start` in `chars`, if any.
+fn number_at(chars: &[char], start: usize) -> Option<(f32, usize)> {
+    let mut end = start;
+    while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+        end += 1;
+    }
+    let number: String = chars[start..end].iter().collect();
+    number.parse::<f32>().ok().map(|value| (value, end))
+}
+
+/// Reads `T5 D10` or `T5 D=10.` out of a tool list comment.
+fn header_diameter(comment: &str) -> Option<(usize, f32)> {
+    let mut t_number = None;
+    let mut diameter = None;
+    for word in comment.split_whitespace() {
+        let word = word.to_ascii_uppercase();
+        if let Some(number) = word.strip_prefix('T') {
+            t_number = t_number.or(number.parse::<usize>().ok());
+        } else if let Some(number) = word.strip_prefix('D') {
+            diameter = diameter.or(number.trim_start_matches('=').parse::<f32>().ok());
+        }
+    }
+    Some((t_number?, diameter?))
+}
+
+/// Splits a line into its code and the comments inside parentheses.
+fn split_comments(line: &str) -> (String, Vec<String>) {
+    let mut code = String::new();
+    let mut comments = Vec::new();
+    let mut comment: Option<String> = None;
+    for c in line.chars() {
+        match (&mut comment, c) {
+            (None, '(') => comment = Some(String::new()),
+            (None, ';') => break,
+            (None, c) => code.push(c),
+            (Some(_), ')') => comments.extend(comment.take()),
+            (Some(text), c) => text.push(c),
+        }
+    }
+    comments.extend(comment);
+    (code, comments)
+}
+
+/// Heidenhain `TOOL CALL 5 Z S3000` or `TOOL CALL "MILL_10" Z`.
+fn heidenhain_tool_call(code: &str) -> Option<ToolReference> {
+    let upper = code.to_ascii_uppercase();
+    let index = upper.find("TOOL CALL")?;
+    let rest = code[index + "TOOL CALL".len()..].trim_start();
+    if let Some(quoted) = rest.strip_prefix('"') {
+        return quoted
+            .split('"')
+            .next()
+            .map(|name| ToolReference::Name(name.to_string()));
+    }
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse::<usize>().ok().map(ToolReference::Number)
+}
+
+/// A T word or `TOOL CALL` found in a program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub line: usize, // 1-based line number in the program
+    pub tool: ToolReference,
+    pub tool_change: bool, // M6 on the same or a following line, always true for TOOL CALL
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramScan {
+    pub calls: Vec<ToolCall>,
+    pub header_diameters: Vec<(usize, f32)>, // (T-number, diameter) from header comments
+}
+
+/// Extracts every tool call of a part program in order, together with the diameters announced
+/// in header comments. With `t_word_offset` the last two digits of a T word of three or more
+/// digits are the offset number, as in Fanuc lathe programs.
+pub fn scan_program(program: &str, t_word_offset: bool) -> ProgramScan {
+    let mut calls = Vec::new();
+    let mut header_diameters = Vec::new();
+    for (i, line) in program.lines().enumerate() {
+        let line_number = i + 1;
+        let (code, comments) = split_comments(line);
+        header_diameters.extend(
+            comments
+                .iter()
+                .filter_map(|comment| header_diameter(comment)),
+        );
+        if let Some(tool) = heidenhain_tool_call(&code) {
+            calls.push(ToolCall {
+                line: line_number,
+                tool,
+                tool_change: true,
+            });
+            continue;
+        }
+
+        let chars: Vec<char> = code.to_ascii_uppercase().chars().collect();
+        let mut t_number = None;
+        let mut tool_change = false;
+        for (index, c) in chars.iter().enumerate() {
+            let starts_word = index == 0 || !chars[index - 1].is_ascii_alphabetic();
+            if !starts_word {
+                continue;
+            }
+            match (c, number_at(&chars, index + 1)) {
+                ('T', Some((value, end))) => {
+                    let digits = end - index - 1;
+                    t_number = Some(if t_word_offset && digits >= 3 {
+                        value as usize / 100
+                    } else {
+                        value as usize
+                    });
+                }
+                ('M', Some((value, _))) if value == 6.0 => tool_change = true,
+                _ => {}
+            }
+        }
+        if let Some(t_number) = t_number {
+            calls.push(ToolCall {
+                line: line_number,
+                tool: ToolReference::Number(t_number),
+                tool_change,
+            });
+        } else if tool_change {
+            // M6 on its own line changes to the tool selected by the last T word
+            if let Some(call) = calls.last_mut() {
+                call.tool_change = true;
+            }
+        }
+    }
+    ProgramScan {
+        calls,
+        header_diameters,
+    }
+}
+
+/// Lists the tools a program needs and looks each of them up in the magazines.
+pub fn check_program(
+    program: &str,
+    magazines: &[Magazine],
+    t_word_offset: bool,
+) -> Vec<ToolRequirement> {
+    let scan = scan_program(program, t_word_offset);
+    let mut requirements: Vec<ToolRequirement> = Vec::new();
+    for ToolCall {
+        line,
+        tool,
+        tool_change,
+    } in scan.calls
+    {
+        if let Some(requirement) = requirements.iter_mut().find(|r| r.tool == tool) {
+            requirement.calls += 1;
+            requirement.tool_changes += tool_change as usize;
+            continue;
+        }
+        let expected_diameter = match tool {
+            ToolReference::Number(t_number) => scan
+                .header_diameters
+                .iter()
+                .find(|(header_t_number, _)| *header_t_number == t_number)
+                .map(|(_, diameter)| *diameter),
+            ToolReference::Name(_) => None,
+        };
+        let mut requirement = ToolRequirement {
+            tool: tool.clone(),
+            first_line: line,
+            calls: 1,
+            tool_changes: tool_change as usize,
+            expected_diameter,
+            magazine: None,
+            slot: None,
+            tool_name: None,
+            loaded_diameter: None,
+        };
+        let location = magazines.iter().find_map(|magazine| {
+            magazine
+                .contents
+                .iter()
+                .find(|slot| match (&tool, &slot.tool) {
+                    (ToolReference::Number(t_number), Some(_)) => slot.t_number == *t_number,
+                    // TNC programs often write spaces in tool names as underscores
+                    (ToolReference::Name(name), Some(loaded)) => loaded
+                        .get_name()
+                        .replace(' ', "_")
+                        .eq_ignore_ascii_case(&name.replace(' ', "_")),
+                    (_, None) => false,
+                })
+                .map(|slot| (magazine, slot))
+        });
+        if let Some((magazine, slot)) = location {
+            let loaded = slot.tool.as_ref().unwrap();
+            requirement.magazine = Some(magazine.name.clone());
+            requirement.slot = Some(slot.pocket);
+            requirement.tool_name = Some(loaded.get_name());
+            requirement.loaded_diameter = Some(loaded.get_diameter()).filter(|d| *d > 0.0);
+        }
+        requirements.push(requirement);
+    }
+    requirements
+}
+
+const T_WORD_OFFSET: &str = "T word includes offset (T0101)";
+
+pub fn program_check(app: &mut ManagingApp, ctx: &egui::Context) {
+    let mut is_window_open = true;
+    let mut check = false;
+    let lathe = selected_machine_type(app) == Some(MachineType::Lathe);
+    egui::Window::new("Program check")
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let fields = &mut app.gui_singletons.program_check;
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut fields.path);
+                if ui.button("Load").clicked() {
+                    match std::fs::read_to_string(&fields.path) {
+                        Ok(program) => {
+                            fields.program = program;
+                            fields.status = None;
+                            check = true;
+                        }
+                        Err(err) => {
+                            fields.status = Some(format!("Could not load {}: {}", fields.path, err))
+                        }
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                check |= ui
+                    .button("Check")
+                    .on_hover_text("Compare the tool calls with the selected machine")
+                    .clicked();
+                if lathe {
+                    ui.add_enabled(false, egui::Checkbox::new(&mut true, T_WORD_OFFSET))
+                        .on_disabled_hover_text("Lathe programs call tools as TxxYY");
+                } else {
+                    ui.checkbox(&mut fields.t_word_offset, T_WORD_OFFSET);
+                }
+                if let Some(status) = &fields.status {
+                    ui.label(status);
+                }
+            });
+            requirements_table(ui, &fields.requirements);
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .id_source("program_text")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut app.gui_singletons.program_check.program)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+
+    if check {
+        let magazines = app
+            .selections
+            .machine
+            .and_then(|index| app.machines.get(index))
+            .map(|machine| machine.magazines.as_slice())
+            .unwrap_or_default();
+        let fields = &mut app.gui_singletons.program_check;
+        let t_word_offset = lathe || fields.t_word_offset;
+        fields.requirements = check_program(&fields.program, magazines, t_word_offset);
+        let missing = fields
+            .requirements
+            .iter()
+            .filter(|requirement| requirement.status() != RequirementStatus::Loaded)
+            .count();
+        fields.status = Some(format!(
+            "{} tools required, {} with problems",
+            fields.requirements.len(),
+            missing
+        ));
+    }
+    if !is_window_open {
+        reset_states(app);
+    }
+}
+
+fn requirements_table(ui: &mut egui::Ui, requirements: &[ToolRequirement]) {
+    if requirements.is_empty() {
+        return;
+    }
+    TableBuilder::new(ui)
+        .columns(Column::auto().resizable(true).clip(false), 7)
+        .max_scroll_height(300.0)
+        .header(20.0, |mut header| {
+            for title in [
+                "Tool", "Line", "Calls", "Magazine", "Slot", "Loaded", "Status",
+            ] {
+                header.col(|ui| {
+                    ui.strong(title);
+                });
+            }
+        })
+        .body(|mut body| {
+            for requirement in requirements.iter() {
+                body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        ui.label(requirement.tool.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(requirement.first_line.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(format!(
+                            "{} ({} M6)",
+                            requirement.calls, requirement.tool_changes
+                        ));
+                    });
+                    row.col(|ui| {
+                        ui.label(requirement.magazine.clone().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        if let Some(slot) = requirement.slot {
+                            ui.label(format!("Slot {}", slot));
+                        }
+                    });
+                    row.col(|ui| {
+                        if let Some(name) = &requirement.tool_name {
+                            match requirement.loaded_diameter {
+                                Some(diameter) => ui.label(format!("{} Ø{}", name, diameter)),
+                                None => ui.label(name),
+                            };
+                        }
+                    });
+                    row.col(|ui| {
+                        let status = requirement.status();
+                        let color = match status {
+                            RequirementStatus::Loaded => Color32::GREEN,
+                            RequirementStatus::Missing => Color32::RED,
+                            RequirementStatus::DiameterMismatch => Color32::YELLOW,
+                        };
+                        let label = ui.colored_label(color, status.to_string());
+                        if let Some(expected) = requirement.expected_diameter {
+                            label.on_hover_text(format!("Program expects Ø{}", expected));
+                        }
+                    });
+                });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offset_transfer::test_support::{magazine, mill};
+
+    fn tools(scan: &ProgramScan) -> Vec<(usize, ToolReference, bool)> {
+        scan.calls
+            .iter()
+            .map(|call| (call.line, call.tool.clone(), call.tool_change))
+            .collect()
+    }
+
+    #[test]
+    fn t_word_and_m6_on_one_line() {
+        let scan = scan_program("N10 T5 M6\nN20 G0 X0", false);
+        assert_eq!(tools(&scan), [(1, ToolReference::Number(5), true)]);
+    }
+
+    #[test]
+    fn m6_on_its_own_line_changes_to_the_last_t_word() {
+        let scan = scan_program("T5\nM6\nT6", false);
+        assert_eq!(
+            tools(&scan),
+            [
+                (1, ToolReference::Number(5), true),
+                (3, ToolReference::Number(6), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn t_word_offset_splits_lathe_t_words() {
+        let program = "T0101\nT1\nT1212";
+        let numbers = |t_word_offset| {
+            scan_program(program, t_word_offset)
+                .calls
+                .into_iter()
+                .map(|call| call.tool)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            numbers(false),
+            [101, 1, 1212].map(ToolReference::Number).to_vec()
+        );
+        assert_eq!(
+            numbers(true),
+            [1, 1, 12].map(ToolReference::Number).to_vec()
+        );
+    }
+
+    #[test]
+    fn heidenhain_tool_calls() {
+        let scan = scan_program("1 TOOL CALL 5 Z S3000\n2 TOOL CALL \"MILL_10\" Z", false);
+        assert_eq!(
+            tools(&scan),
+            [
+                (1, ToolReference::Number(5), true),
+                (2, ToolReference::Name("MILL_10".to_string()), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn header_diameter_is_compared_with_the_loaded_tool() {
+        let mut magazines = vec![magazine("Magazine 0", 6)];
+        let slot = &mut magazines[0].contents[4];
+        slot.tool = mill();
+        if let Some(tool) = &mut slot.tool {
+            tool.set_name("Mill 10".to_string());
+            tool.set_diameter(12.0);
+        }
+        let requirements = check_program(
+            "(T5 D10 MILL)\nT5 M6\nTOOL CALL \"MILL_10\" Z\nT6 M6",
+            &magazines,
+            false,
+        );
+        let statuses: Vec<RequirementStatus> = requirements
+            .iter()
+            .map(|requirement| requirement.status())
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                RequirementStatus::DiameterMismatch,
+                RequirementStatus::Loaded,
+                RequirementStatus::Missing,
+            ]
+        );
+        assert_eq!(requirements[0].expected_diameter, Some(10.0));
+        assert_eq!(requirements[0].slot, Some(4));
+    }
+}
@@ -5,8 +5,38 @@ use egui::Color32;
 use strum::{Display, EnumIter, EnumString};
 
 use crate::{
-    adapter::Adapter, calculations::calculations::*, collet::Collet, drill::Drill, holder::Holder,
-    hydraulic::Hydraulic, mill::Mill, tool::Tool, trigoninsert::TrigonInsert, Machine,
+    adapter::Adapter,
+    assembly::AssemblyBuilderFields,
+    boring_bar::BoringBar,
+    calculations::calculations::*,
+    chamfer_mill::ChamferMill,
+    collet::Collet,
+    drill::Drill,
+    end_mill::{EndMill, EndMillShape},
+    external_holder::ExternalHolder,
+    face_mill::FaceMill,
+    geometry::{ToolGeometry, ToolMaterial},
+    grooving_blade::GroovingBlade,
+    holder::Holder,
+    hydraulic::Hydraulic,
+    hydraulic_chuck::HydraulicChuck,
+    iso_insert::IsoInsert,
+    material::MaterialCatalogueFields,
+    mill::Mill,
+    milling_arbor::MillingArbor,
+    offset_transfer::OffsetTransferFields,
+    program_check::ProgramCheckFields,
+    reamer::Reamer,
+    shrink_fit::ShrinkFit,
+    tap::Tap,
+    thread_mill::ThreadMill,
+    threading_holder::ThreadingHolder,
+    tool::{Oversize, Tool, ToolCategory},
+    transfer::SlotAddress,
+    trigoninsert::TrigonInsert,
+    turret::MagazineKind,
+    weldon::Weldon,
+    ChuckMCodes, Machine, MachineLimits, MachineType, PendingRemoval,
 };
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
@@ -25,10 +55,21 @@ pub struct MagazineLibraryMovingSelections {
     pub selected_holder_index_magazine: Option<usize>,
     pub selected_adapter_index_magazine: Option<usize>,
     pub selected_comment_index_magazine: Option<usize>,
+    pub selected_slot_index_magazine: Option<usize>,
+    pub selected_assembly_index_magazine: Option<usize>,
 
     pub selected_tool_index_library: Option<usize>,
     pub selected_holder_index_library: Option<usize>,
     pub selected_adapter_index_library: Option<usize>,
+    pub selected_assembly_index_library: Option<usize>,
+
+    pub mount_violations: Vec<String>, // Reasons the pending move is blocked
+    pub mount_override: bool,
+    pub move_refusal: Option<String>, // Why the pending move cannot be done at all
+    pub swap_target_index_magazine: Option<usize>, // Swapped with selected_slot_index_magazine
+    pub from_drop: bool, // Move started by drag and drop, keeps the current window open
+    pub transfer_target: SlotAddress, // Where selected_slot_index_magazine is moved to
+    pub transfer_requested: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
@@ -52,6 +93,13 @@ pub enum AppState {
     ShowLibrary,
     Settings,
     Calculations,
+    OffsetTransfer,
+    ProgramCheck,
+    Materials,
+    BuildAssembly,
+    MachineCapabilities,
+    EditMachine,
+    History,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
@@ -64,6 +112,21 @@ pub enum MoveStates {
     AdapterToMagazine,
     AdapterToLibrary,
     EditComment,
+    EditSlot,
+    AssemblyToMagazine,
+    AssemblyToLibrary,
+    SwapSlots,
+    TransferSlot,
+}
+
+/// What is being dragged between the library and the magazine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragItem {
+    LibraryTool(usize),
+    LibraryHolder(usize),
+    LibraryAdapter(usize),
+    LibraryAssembly(usize),
+    Slot(usize), // Pocket in the displayed magazine
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
@@ -100,6 +163,7 @@ pub enum MagazineContentType {
     Tool,
     Holder,
     Adapter,
+    Assembly,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
@@ -108,6 +172,9 @@ pub enum SortBy {
     Slot,
     Diameter,
     Degree,
+    ToolType,
+    Flutes,
+    OverallLength,
 }
 
 impl fmt::Display for SortBy {
@@ -116,6 +183,9 @@ impl fmt::Display for SortBy {
             SortBy::Slot => write!(f, "Slot"),
             SortBy::Diameter => write!(f, "Diameter"),
             SortBy::Degree => write!(f, "Degree"),
+            SortBy::ToolType => write!(f, "Tool type"),
+            SortBy::Flutes => write!(f, "Flutes"),
+            SortBy::OverallLength => write!(f, "Overall length"),
         }
     }
 }
@@ -146,6 +216,43 @@ pub struct ColorSettings {
     pub color: Color32,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct WearLimit {
+    pub length: f32,
+    pub radius: f32,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct WearLimits {
+    pub rotating: WearLimit,
+    pub lathe_insert: WearLimit,
+}
+
+impl Default for WearLimits {
+    fn default() -> Self {
+        Self {
+            rotating: WearLimit {
+                length: 0.5,
+                radius: 0.2,
+            },
+            lathe_insert: WearLimit {
+                length: 0.3,
+                radius: 0.3,
+            },
+        }
+    }
+}
+
+impl WearLimits {
+    pub fn get(&self, category: &ToolCategory) -> Option<&WearLimit> {
+        match category {
+            ToolCategory::Rotating => Some(&self.rotating),
+            ToolCategory::LatheInsert => Some(&self.lathe_insert),
+            ToolCategory::All | ToolCategory::Empty => None,
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct GuiSingletons {
     pub rotating_tools: Vec<Tool>,
@@ -156,9 +263,25 @@ pub struct GuiSingletons {
 
     pub tool_filter: Option<ToolState>,
     pub sort_by: SortBy,
+    #[serde(default)]
+    pub material_filter: Option<ToolMaterial>,
 
     pub color_settings_state: ColorSettingsState,
     pub color_settings: ColorSettings,
+    #[serde(default)]
+    pub wear_limits: WearLimits,
+    #[serde(default)]
+    pub offset_transfer: OffsetTransferFields,
+    #[serde(default)]
+    pub program_check: ProgramCheckFields,
+    #[serde(default)]
+    pub material_catalogue: MaterialCatalogueFields,
+    #[serde(default)]
+    pub assembly_builder: AssemblyBuilderFields,
+    #[serde(default)]
+    pub magazine_kind: MagazineKind, // Kind of magazines a new machine gets
+    #[serde(skip)]
+    pub pending_removal: Option<PendingRemoval>,
 
     pub universal_calculations: UniversalCalculations,
 }
@@ -170,11 +293,16 @@ impl Default for GuiSingletons {
             name: "Drill".to_string(),
             diameter: 10.0,
             color: Color32::RED,
+            oversize: Oversize::default(),
+            geometry: ToolGeometry::drill(),
+            point_angle: 140.0,
         };
         let mill = Mill {
             name: "Mill".to_string(),
             diameter: 10.0,
             color: Color32::BLUE,
+            oversize: Oversize::default(),
+            geometry: ToolGeometry::default(),
         };
         // Insert tools
         let trigon_insert = TrigonInsert {
@@ -183,27 +311,89 @@ impl Default for GuiSingletons {
             color: Color32::GREEN,
         };
         // Holders
-        let collet = Collet {
-            name: "Collet".to_string(),
-            color: Color32::LIGHT_BLUE,
-        };
+        let collet = Collet::default();
         // Adapters
         let hydraulic = Hydraulic {
             name: "Hydraulic".to_string(),
             color: Color32::GREEN,
+            spindle_interface: None,
+            holder_interface: None,
+        };
+        let end_mill = |name: &str, shape, corner_radius, color| {
+            Tool::EndMill(EndMill {
+                name: name.to_string(),
+                shape,
+                corner_radius,
+                color,
+                ..EndMill::default()
+            })
         };
-        let mut rotating_tools = Vec::<Tool>::new();
-        rotating_tools.push(Tool::Drill(drill.clone()));
-        rotating_tools.push(Tool::Mill(mill.clone())); // vec![Tool::Drill(drill), Tool::Mill(mill)];
-        let insert_tools = vec![Tool::TrigonInsert(trigon_insert)];
-        let holders = vec![Holder::Collet(collet)];
+        let rotating_tools = vec![
+            Tool::Drill(drill),
+            Tool::Mill(mill),
+            end_mill("End mill", EndMillShape::Square, 0.0, Color32::BLUE),
+            end_mill(
+                "Ball nose",
+                EndMillShape::BallNose,
+                0.0,
+                Color32::from_rgb(80, 80, 255),
+            ),
+            end_mill(
+                "Bull nose",
+                EndMillShape::BullNose,
+                1.0,
+                Color32::from_rgb(0, 120, 255),
+            ),
+            Tool::ChamferMill(ChamferMill::default()),
+            Tool::FaceMill(FaceMill::default()),
+            Tool::Tap(Tap::default()),
+            Tool::Reamer(Reamer::default()),
+            Tool::ThreadMill(ThreadMill::default()),
+        ];
+        let iso_insert = |code: &str, color| {
+            let mut insert = IsoInsert::from_code(code).unwrap_or_default();
+            insert.color = color;
+            Tool::IsoInsert(insert)
+        };
+        let insert_tools = vec![
+            Tool::TrigonInsert(trigon_insert),
+            iso_insert("CNMG 120408-PM", Color32::GREEN),
+            iso_insert("DNMG 150608-PM", Color32::from_rgb(0, 160, 80)),
+            iso_insert("TNMG 160408-PM", Color32::from_rgb(120, 200, 0)),
+            iso_insert("VNMG 160404-PF", Color32::from_rgb(0, 200, 160)),
+            iso_insert("WNMG 080408-PM", Color32::from_rgb(80, 160, 0)),
+            iso_insert("SNMG 120408-PR", Color32::DARK_GREEN),
+            iso_insert("RCMT 1204M0", Color32::from_rgb(160, 220, 120)),
+        ];
+        let external_holder = |code: &str, color| {
+            let mut holder = ExternalHolder::from_code(code).unwrap_or_default();
+            holder.color = color;
+            Holder::ExternalHolder(holder)
+        };
+        let holders = vec![
+            Holder::Collet(collet),
+            Holder::ShrinkFit(ShrinkFit::default()),
+            Holder::Weldon(Weldon::default()),
+            Holder::HydraulicChuck(HydraulicChuck::default()),
+            Holder::MillingArbor(MillingArbor::default()),
+            external_holder("PCLNR 2525M12", Color32::from_rgb(200, 160, 60)),
+            external_holder("PDJNR 2525M15", Color32::from_rgb(220, 180, 80)),
+            external_holder("MVJNR 2525M16", Color32::from_rgb(180, 140, 40)),
+            Holder::BoringBar(BoringBar::default()),
+            Holder::GroovingBlade(GroovingBlade::default()),
+            Holder::ThreadingHolder(ThreadingHolder::default()),
+        ];
         let adapters = vec![Adapter::Hydraulic(hydraulic)];
         let machine = Machine {
             name: "Machine".to_string(),
+            machine_type: MachineType::default(),
             number_of_magazines: 1,
             magazine_size: 1,
             magazines: vec![],
             current_magazine: None,
+            chuck_m_codes: ChuckMCodes::default(),
+            spindle_interface: None,
+            limits: MachineLimits::default(),
         };
         GuiSingletons {
             rotating_tools,
@@ -213,12 +403,50 @@ impl Default for GuiSingletons {
             machine,
             tool_filter: None,
             sort_by: SortBy::Slot,
+            material_filter: None,
             color_settings_state: ColorSettingsState::Rotating,
             color_settings: ColorSettings {
                 index: None,
                 color: Color32::RED,
             },
+            wear_limits: WearLimits::default(),
+            offset_transfer: OffsetTransferFields::default(),
+            program_check: ProgramCheckFields::default(),
+            material_catalogue: MaterialCatalogueFields::default(),
+            assembly_builder: AssemblyBuilderFields::default(),
+            magazine_kind: MagazineKind::default(),
+            pending_removal: None,
             universal_calculations: UniversalCalculations::default(),
         }
     }
 }
+
+impl GuiSingletons {
+    /// Adds the templates of tool, holder and adapter types introduced after the settings were
+    /// saved, keeping the colours already chosen for the others.
+    pub fn add_missing_templates(&mut self) {
+        let defaults = GuiSingletons::default();
+        fn merge<T>(saved: &mut Vec<T>, defaults: Vec<T>, get_type: impl Fn(&T) -> String) {
+            for template in defaults {
+                if !saved
+                    .iter()
+                    .any(|item| get_type(item) == get_type(&template))
+                {
+                    saved.push(template);
+                }
+            }
+        }
+        merge(
+            &mut self.rotating_tools,
+            defaults.rotating_tools,
+            Tool::get_type,
+        );
+        merge(
+            &mut self.insert_tools,
+            defaults.insert_tools,
+            Tool::get_type,
+        );
+        merge(&mut self.holders, defaults.holders, Holder::get_type);
+        merge(&mut self.adapters, defaults.adapters, Adapter::get_type);
+    }
+}
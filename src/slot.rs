@@ -0,0 +1,378 @@
+use std::fmt;
+
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::adapter::*;
+use crate::assembly::AssemblyTag;
+use crate::comment::*;
+use crate::holder::*;
+use crate::resources::*;
+use crate::tool::*;
+use crate::turret::{MagazineKind, TurretStation};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum SlotState {
+    #[default]
+    Empty,
+    Reserved,
+    Blocked,
+    Occupied,
+}
+
+impl fmt::Display for SlotState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotState::Empty => write!(f, "Empty"),
+            SlotState::Reserved => write!(f, "Reserved"),
+            SlotState::Blocked => write!(f, "Blocked"),
+            SlotState::Occupied => write!(f, "Occupied"),
+        }
+    }
+}
+
+/// H and D offset data for a pocket, in millimeters.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ToolOffsets {
+    pub length_geometry: f32,
+    pub radius_geometry: f32,
+    pub length_wear: f32,
+    pub radius_wear: f32,
+}
+
+impl ToolOffsets {
+    pub fn length_total(&self) -> f32 {
+        self.length_geometry + self.length_wear
+    }
+
+    pub fn radius_total(&self) -> f32 {
+        self.radius_geometry + self.radius_wear
+    }
+
+    pub fn wear_exceeded(&self, limit: &WearLimit) -> bool {
+        self.length_wear.abs() > limit.length || self.radius_wear.abs() > limit.radius
+    }
+}
+
+/// Tool life in minutes of cutting time.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ToolLife {
+    pub max_time: f32, // 0 means no limit
+    pub current_time: f32,
+}
+
+impl ToolLife {
+    pub fn is_expired(&self) -> bool {
+        self.max_time > 0.0 && self.current_time >= self.max_time
+    }
+}
+
+/// One pocket of a magazine and whatever is mounted in it.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub pocket: usize,   // Physical pocket, also the index into `Magazine::contents`
+    pub t_number: usize, // T-number the NC program calls the pocket by
+    pub state: SlotState,
+    pub tool: Option<Tool>,
+    pub holder: Option<Holder>,
+    pub adapter: Option<Adapter>,
+    pub comment: Comment,
+    #[serde(default)]
+    pub offsets: ToolOffsets,
+    #[serde(default)]
+    pub sister_tool: Option<usize>, // T-number of the replacement tool
+    #[serde(default)]
+    pub tool_life: ToolLife,
+    #[serde(default)]
+    pub assembly: Option<AssemblyTag>, // Set while the contents are a loaded tool assembly
+    #[serde(default)]
+    pub blocked_by: Option<usize>, // Pocket of the oversized tool keeping this pocket blocked
+    #[serde(default)]
+    pub station: Option<TurretStation>, // Set for the stations of a turret
+    #[serde(default)]
+    pub locked: bool, // Tool lock, the control must not call the tool
+}
+
+impl Slot {
+    pub fn new(pocket: usize) -> Self {
+        Self {
+            pocket,
+            t_number: pocket + 1,
+            state: SlotState::Empty,
+            tool: None,
+            holder: None,
+            adapter: None,
+            comment: Comment::default(),
+            offsets: ToolOffsets::default(),
+            sister_tool: None,
+            tool_life: ToolLife::default(),
+            assembly: None,
+            blocked_by: None,
+            station: None,
+            locked: false,
+        }
+    }
+
+    /// Empty pocket of a magazine of the given kind, turret pockets are stations.
+    pub fn new_in(pocket: usize, kind: MagazineKind) -> Self {
+        let station = match kind {
+            MagazineKind::Chain => None,
+            MagazineKind::Turret => Some(TurretStation::default()),
+        };
+        Self {
+            station,
+            ..Slot::new(pocket)
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.tool.is_some() || self.holder.is_some() || self.adapter.is_some()
+    }
+
+    /// One line naming what the slot holds, `None` when it is not loaded.
+    pub fn contents_summary(&self) -> Option<String> {
+        if !self.is_loaded() {
+            return None;
+        }
+        if let Some(assembly) = &self.assembly {
+            return Some(format!(
+                "Slot {}: assembly A{} {}",
+                self.pocket, assembly.id, assembly.name
+            ));
+        }
+        let parts: Vec<String> = [
+            self.tool.as_ref().map(|tool| tool.get_name()),
+            self.holder.as_ref().map(|holder| holder.get_name()),
+            self.adapter.as_ref().map(|adapter| adapter.get_name()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Some(format!("Slot {}: {}", self.pocket, parts.join(", ")))
+    }
+
+    /// Exchanges what is mounted with another slot. Offsets, tool life, sister tool, tool lock
+    /// and comment go with the contents, the T-number and turret station stay with the pocket.
+    pub fn exchange_contents(&mut self, other: &mut Slot) {
+        std::mem::swap(&mut self.tool, &mut other.tool);
+        std::mem::swap(&mut self.holder, &mut other.holder);
+        std::mem::swap(&mut self.adapter, &mut other.adapter);
+        std::mem::swap(&mut self.assembly, &mut other.assembly);
+        std::mem::swap(&mut self.comment, &mut other.comment);
+        std::mem::swap(&mut self.offsets, &mut other.offsets);
+        std::mem::swap(&mut self.sister_tool, &mut other.sister_tool);
+        std::mem::swap(&mut self.tool_life, &mut other.tool_life);
+        std::mem::swap(&mut self.locked, &mut other.locked);
+        self.refresh_state();
+        other.refresh_state();
+    }
+
+    pub fn accepts_contents(&self) -> bool {
+        self.state != SlotState::Blocked
+    }
+
+    /// Explains why nothing can be loaded into the pocket, `None` when it accepts contents.
+    pub fn blocked_refusal(&self) -> Option<String> {
+        if self.accepts_contents() {
+            return None;
+        }
+        Some(match self.blocked_by {
+            Some(pocket) => format!("Blocked by oversized tool in slot {}", pocket),
+            None => format!("Slot {} is blocked", self.pocket),
+        })
+    }
+
+    pub fn wear_exceeded(&self, wear_limits: &WearLimits) -> bool {
+        match &self.tool {
+            Some(tool) => wear_limits
+                .get(&tool.get_category())
+                .map(|limit| self.offsets.wear_exceeded(limit))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Recomputes occupancy after the mounted contents changed. Blocked pockets stay blocked
+    /// and a reservation is kept until something is loaded into the pocket.
+    pub fn refresh_state(&mut self) {
+        self.state = match self.state {
+            SlotState::Blocked => SlotState::Blocked,
+            _ if self.is_loaded() => SlotState::Occupied,
+            SlotState::Reserved => SlotState::Reserved,
+            _ => SlotState::Empty,
+        };
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("Slot {}", self.pocket));
+            ui.separator();
+            ui.label(format!("T{}", self.t_number));
+            if let Some(pocket) = self.blocked_by {
+                ui.separator();
+                ui.colored_label(egui::Color32::GRAY, format!("Blocked by slot {}", pocket))
+                    .on_hover_text("Reserved for the oversized tool next to it");
+            } else if self.state != SlotState::Occupied && self.state != SlotState::Empty {
+                ui.separator();
+                ui.label(self.state.to_string());
+            }
+            if let Some(station) = &self.station {
+                station.display(ui);
+            }
+            if let Some(sister_tool) = self.sister_tool {
+                ui.label(format!("→ T{}", sister_tool))
+                    .on_hover_text("Sister tool");
+            }
+            if let Some(assembly) = &self.assembly {
+                ui.label(format!("A{}", assembly.id))
+                    .on_hover_text(format!("Assembly {}", assembly.name));
+            }
+            if self.locked {
+                ui.colored_label(egui::Color32::RED, "Locked")
+                    .on_hover_text("Tool lock, the control will not call this tool");
+            }
+            if self.tool_life.is_expired() {
+                ui.colored_label(egui::Color32::RED, "Life expired");
+            }
+        });
+    }
+}
+
+/// Layout magazine contents were persisted with before `Slot` existed.
+type LegacySlot = (
+    usize,
+    Option<Tool>,
+    Option<Holder>,
+    Option<Adapter>,
+    Comment,
+);
+
+impl From<LegacySlot> for Slot {
+    fn from((pocket, tool, holder, adapter, comment): LegacySlot) -> Self {
+        let mut slot = Slot {
+            tool,
+            holder,
+            adapter,
+            comment,
+            ..Slot::new(pocket)
+        };
+        slot.refresh_state();
+        slot
+    }
+}
+
+/// Deserializes magazine contents saved either as `Slot`s or as legacy tuples.
+pub fn deserialize_slots<'de, D>(deserializer: D) -> Result<Vec<Slot>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let slots = Vec::<VersionedSlot>::deserialize(deserializer)?;
+    Ok(slots.into_iter().map(|slot| slot.0).collect())
+}
+
+struct VersionedSlot(Slot);
+
+impl<'de> Deserialize<'de> for VersionedSlot {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VersionedSlotVisitor)
+    }
+}
+
+struct VersionedSlotVisitor;
+
+impl<'de> Visitor<'de> for VersionedSlotVisitor {
+    type Value = VersionedSlot;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a magazine slot")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let legacy = LegacySlot::deserialize(SeqAccessDeserializer::new(seq))?;
+        Ok(VersionedSlot(Slot::from(legacy)))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let slot = Slot::deserialize(MapAccessDeserializer::new(map))?;
+        Ok(VersionedSlot(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magazine::Magazine;
+    use crate::mill::Mill;
+
+    #[test]
+    fn legacy_tuples_load_as_slots() {
+        // Saved before `Slot` existed, a pocket was (pocket, tool, holder, adapter, comment)
+        let magazine: Magazine = ron::from_str(
+            r#"(
+                name: "Magazine 0",
+                contents: [
+                    (0, None, None, None, (comment: "__")),
+                    (
+                        1,
+                        Some(Mill((name: "Mill", diameter: 10.0, color: ((0, 0, 255, 255))))),
+                        None,
+                        None,
+                        (comment: "Roughing"),
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let mut loaded = Slot::new(1);
+        loaded.state = SlotState::Occupied;
+        loaded.tool = Some(Tool::Mill(Mill::default()));
+        loaded.comment.comment = "Roughing".to_string();
+        assert_eq!(magazine.contents, [Slot::new(0), loaded]);
+    }
+
+    #[test]
+    fn slots_load_with_pocket_and_state() {
+        let magazine: Magazine = ron::from_str(
+            r#"(
+                name: "Magazine 0",
+                contents: [
+                    (
+                        pocket: 0,
+                        t_number: 7,
+                        state: Reserved,
+                        tool: None,
+                        holder: None,
+                        adapter: None,
+                        comment: (comment: "__"),
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let mut reserved = Slot::new(0);
+        reserved.t_number = 7;
+        reserved.state = SlotState::Reserved;
+        assert_eq!(magazine.contents, [reserved]);
+
+        let mut blocked = Slot::new(1);
+        blocked.state = SlotState::Blocked;
+        blocked.blocked_by = Some(0);
+        blocked.locked = true;
+        let magazine = Magazine {
+            contents: vec![Slot::new(0), blocked],
+            ..magazine
+        };
+        let saved = ron::to_string(&magazine).unwrap();
+        assert_eq!(ron::from_str::<Magazine>(&saved).unwrap(), magazine);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ChamferMill {
+    pub name: String,
+    pub diameter: f32,     // Largest cutting diameter
+    pub tip_diameter: f32, // Diameter at the tip, 0 for a pointed tool
+    pub angle: f32,        // Included angle [°], 90 cuts a 45° chamfer
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+}
+
+impl Default for ChamferMill {
+    fn default() -> Self {
+        Self {
+            name: "Chamfer mill".to_string(),
+            diameter: 10.0,
+            tip_diameter: 0.0,
+            angle: 90.0,
+            color: Color32::from_rgb(0, 160, 160),
+            oversize: Oversize::default(),
+        }
+    }
+}
+
+impl ChamferMill {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 0.001..=200.0).text("Diameter"));
+        ui.add(egui::Slider::new(&mut self.tip_diameter, 0.0..=self.diameter).text("Tip diameter"));
+        ui.add(egui::Slider::new(&mut self.angle, 10.0..=150.0).text("Included angle"));
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(format!("Ø{}", self.diameter));
+            ui.label(format!("{}°", self.angle))
+                .on_hover_text(format!("Tip Ø{}", self.tip_diameter));
+            self.oversize.display(ui);
+        });
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        "Chamfer mill".to_string()
+    }
+}
@@ -1,19 +1,34 @@
 use egui::Color32;
 
-use crate::tool::ToolCategory;
+use crate::geometry::ToolGeometry;
+use crate::tool::{Oversize, ToolCategory};
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct Drill {
     pub name: String,
     pub diameter: f32,
     pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+    #[serde(default = "ToolGeometry::drill")]
+    pub geometry: ToolGeometry,
+    #[serde(default = "default_point_angle")]
+    pub point_angle: f32, // [°]
 }
+
+fn default_point_angle() -> f32 {
+    140.0
+}
+
 impl Default for Drill {
     fn default() -> Self {
         Self {
             name: "Drill".to_string(),
             diameter: 10.0,
+            oversize: Oversize::default(),
             color: Color32::RED,
+            geometry: ToolGeometry::drill(),
+            point_angle: default_point_angle(),
         }
     }
 }
@@ -26,6 +41,9 @@ impl Drill {
             ui.text_edit_singleline(&mut self.name);
         });
         ui.add(egui::Slider::new(&mut self.diameter, 0.001..=200.0).text("Diameter"));
+        ui.add(egui::Slider::new(&mut self.point_angle, 60.0..=180.0).text("Point angle"));
+        self.geometry.edit(ui, self.diameter);
+        self.oversize.edit(ui);
         ui.separator();
         *add = ui.button("Add").clicked()
     }
@@ -41,6 +59,10 @@ impl Drill {
                 ui.label("Diameter:");
                 ui.label(&self.diameter.to_string());
             });
+            ui.label(format!("{}°", self.point_angle))
+                .on_hover_text("Point angle");
+            self.geometry.display(ui, self.diameter);
+            self.oversize.display(ui);
         });
     }
 
@@ -52,6 +74,10 @@ impl Drill {
         self.diameter
     }
 
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::geometry::ToolGeometry;
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+/// Shape of the end of an end mill.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndMillShape {
+    #[default]
+    Square,
+    BallNose,
+    BullNose, // Corner radius smaller than half the diameter
+}
+
+impl fmt::Display for EndMillShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndMillShape::Square => write!(f, "Square end mill"),
+            EndMillShape::BallNose => write!(f, "Ball nose end mill"),
+            EndMillShape::BullNose => write!(f, "Bull nose end mill"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct EndMill {
+    pub name: String,
+    pub shape: EndMillShape,
+    pub diameter: f32,
+    pub corner_radius: f32, // Only set for bull nose end mills
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+    #[serde(default)]
+    pub geometry: ToolGeometry,
+}
+
+impl Default for EndMill {
+    fn default() -> Self {
+        Self {
+            name: "End mill".to_string(),
+            shape: EndMillShape::Square,
+            diameter: 10.0,
+            corner_radius: 0.0,
+            color: Color32::BLUE,
+            oversize: Oversize::default(),
+            geometry: ToolGeometry::default(),
+        }
+    }
+}
+
+impl EndMill {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 0.001..=200.0).text("Diameter"));
+        if self.shape == EndMillShape::BullNose {
+            ui.add(
+                egui::Slider::new(&mut self.corner_radius, 0.0..=self.diameter / 2.0)
+                    .text("Corner radius"),
+            );
+        }
+        self.geometry.edit(ui, self.diameter);
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name)
+                .on_hover_text(self.shape.to_string());
+            ui.separator();
+            ui.label(format!("Ø{}", self.diameter));
+            match self.shape {
+                EndMillShape::Square => {}
+                EndMillShape::BallNose | EndMillShape::BullNose => {
+                    ui.label(format!("R{}", self.get_corner_radius()));
+                }
+            }
+            self.geometry.display(ui, self.diameter);
+            self.oversize.display(ui);
+        });
+    }
+
+    /// Radius of the cutting corner, half the diameter for a ball nose.
+    pub fn get_corner_radius(&self) -> f32 {
+        match self.shape {
+            EndMillShape::Square => 0.0,
+            EndMillShape::BallNose => self.diameter / 2.0,
+            EndMillShape::BullNose => self.corner_radius,
+        }
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        self.shape.to_string()
+    }
+}
@@ -0,0 +1,73 @@
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct FaceMill {
+    pub name: String,
+    pub diameter: f32,
+    pub insert_count: u32,
+    pub lead_angle: f32, // Entering angle of the inserts [°], 90 for a square shoulder cutter
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+}
+
+impl Default for FaceMill {
+    fn default() -> Self {
+        Self {
+            name: "Face mill".to_string(),
+            diameter: 63.0,
+            insert_count: 5,
+            lead_angle: 45.0,
+            color: Color32::DARK_BLUE,
+            oversize: Oversize::default(),
+        }
+    }
+}
+
+impl FaceMill {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 10.0..=500.0).text("Diameter"));
+        ui.add(egui::Slider::new(&mut self.insert_count, 1..=40).text("Inserts"));
+        ui.add(egui::Slider::new(&mut self.lead_angle, 10.0..=90.0).text("Lead angle"));
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(format!("Ø{}", self.diameter));
+            ui.label(format!("z={} {}°", self.insert_count, self.lead_angle))
+                .on_hover_text("Inserts and lead angle");
+            self.oversize.display(ui);
+        });
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        "Face mill".to_string()
+    }
+}
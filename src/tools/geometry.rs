@@ -0,0 +1,177 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::material::CuttingToolMaterial;
+
+/// Substrate the cutting edges are made of.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum ToolMaterial {
+    Hss,
+    #[default]
+    Carbide,
+    Cermet,
+    Pcd,
+}
+
+impl ToolMaterial {
+    /// Column of the material table the cutting speed is looked up in. Cermet runs at carbide
+    /// speeds or above, PCD has no column.
+    pub fn cutting_data_material(&self) -> Option<CuttingToolMaterial> {
+        match self {
+            ToolMaterial::Hss => Some(CuttingToolMaterial::Hss),
+            ToolMaterial::Carbide | ToolMaterial::Cermet => Some(CuttingToolMaterial::Carbide),
+            ToolMaterial::Pcd => None,
+        }
+    }
+}
+
+impl fmt::Display for ToolMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolMaterial::Hss => write!(f, "HSS"),
+            ToolMaterial::Carbide => write!(f, "Carbide"),
+            ToolMaterial::Cermet => write!(f, "Cermet"),
+            ToolMaterial::Pcd => write!(f, "PCD"),
+        }
+    }
+}
+
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum Coating {
+    #[default]
+    Uncoated,
+    TiN,
+    TiCN,
+    TiAlN,
+    AlTiN,
+    AlCrN,
+    Dlc,
+    Diamond,
+}
+
+impl fmt::Display for Coating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Coating::Uncoated => write!(f, "Uncoated"),
+            Coating::TiN => write!(f, "TiN"),
+            Coating::TiCN => write!(f, "TiCN"),
+            Coating::TiAlN => write!(f, "TiAlN"),
+            Coating::AlTiN => write!(f, "AlTiN"),
+            Coating::AlCrN => write!(f, "AlCrN"),
+            Coating::Dlc => write!(f, "DLC"),
+            Coating::Diamond => write!(f, "Diamond"),
+        }
+    }
+}
+
+/// Geometry shared by solid drills and mills. Lengths in [mm], angles in [°].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ToolGeometry {
+    pub flutes: u32,
+    pub flute_length: f32,
+    pub overall_length: f32,
+    pub shank_diameter: Option<f32>, // None when the shank has the cutting diameter
+    pub helix_angle: f32,
+    pub material: ToolMaterial,
+    pub coating: Coating,
+}
+
+impl Default for ToolGeometry {
+    fn default() -> Self {
+        Self {
+            flutes: 4,
+            flute_length: 22.0,
+            overall_length: 72.0,
+            shank_diameter: None,
+            helix_angle: 30.0,
+            material: ToolMaterial::Carbide,
+            coating: Coating::TiAlN,
+        }
+    }
+}
+
+impl ToolGeometry {
+    pub fn drill() -> Self {
+        Self {
+            flutes: 2,
+            flute_length: 47.0,
+            overall_length: 89.0,
+            ..Self::default()
+        }
+    }
+
+    pub fn shank_diameter(&self, diameter: f32) -> f32 {
+        self.shank_diameter.unwrap_or(diameter)
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui, diameter: f32) {
+        egui::Grid::new("tool_geometry_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Flutes:");
+                ui.add(egui::DragValue::new(&mut self.flutes).clamp_range(1..=20));
+                ui.end_row();
+                ui.label("Flute length:");
+                ui.add(
+                    egui::DragValue::new(&mut self.flute_length)
+                        .clamp_range(0.0..=self.overall_length),
+                );
+                ui.end_row();
+                ui.label("Overall length:");
+                ui.add(egui::DragValue::new(&mut self.overall_length).clamp_range(1.0..=1000.0));
+                ui.end_row();
+                let mut own_shank = self.shank_diameter.is_some();
+                ui.checkbox(&mut own_shank, "Shank diameter:")
+                    .on_hover_text("Leave unchecked when the shank has the cutting diameter");
+                match (own_shank, &mut self.shank_diameter) {
+                    (true, Some(shank)) => {
+                        ui.add(egui::DragValue::new(shank).clamp_range(0.5..=100.0));
+                    }
+                    (true, None) => self.shank_diameter = Some(diameter),
+                    (false, _) => {
+                        self.shank_diameter = None;
+                        ui.label(format!("Ø{}", diameter));
+                    }
+                }
+                ui.end_row();
+                ui.label("Helix angle:");
+                ui.add(egui::DragValue::new(&mut self.helix_angle).clamp_range(0.0..=60.0));
+                ui.end_row();
+                ui.label("Material:");
+                ui.horizontal(|ui| {
+                    for material in ToolMaterial::iter() {
+                        ui.radio_value(&mut self.material, material, material.to_string());
+                    }
+                });
+                ui.end_row();
+                ui.label("Coating:");
+                egui::ComboBox::from_id_source("tool_coating")
+                    .selected_text(self.coating.to_string())
+                    .show_ui(ui, |ui| {
+                        for coating in Coating::iter() {
+                            ui.selectable_value(&mut self.coating, coating, coating.to_string());
+                        }
+                    });
+                ui.end_row();
+            });
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui, diameter: f32) {
+        ui.label(format!(
+            "z{} {} {}",
+            self.flutes, self.material, self.coating
+        ))
+        .on_hover_text(format!(
+            "Flute length {}, overall length {}, shank Ø{}, helix {}°",
+            self.flute_length,
+            self.overall_length,
+            self.shank_diameter(diameter),
+            self.helix_angle
+        ));
+    }
+}
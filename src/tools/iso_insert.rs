@@ -0,0 +1,483 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::tool::ToolCategory;
+use egui::Color32;
+
+/// Insert shape, first letter of the ISO 1832 code.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum InsertShape {
+    #[default]
+    C, // Rhombic 80°
+    D, // Rhombic 55°
+    T, // Triangular 60°
+    V, // Rhombic 35°
+    W, // Trigon 80°
+    S, // Square 90°
+    R, // Round
+}
+
+impl InsertShape {
+    pub fn letter(&self) -> char {
+        match self {
+            InsertShape::C => 'C',
+            InsertShape::D => 'D',
+            InsertShape::T => 'T',
+            InsertShape::V => 'V',
+            InsertShape::W => 'W',
+            InsertShape::S => 'S',
+            InsertShape::R => 'R',
+        }
+    }
+
+    /// Included angle of the cutting corner [°], `None` for round inserts.
+    pub fn nose_angle(&self) -> Option<f32> {
+        match self {
+            InsertShape::C | InsertShape::W => Some(80.0),
+            InsertShape::D => Some(55.0),
+            InsertShape::T => Some(60.0),
+            InsertShape::V => Some(35.0),
+            InsertShape::S => Some(90.0),
+            InsertShape::R => None,
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        InsertShape::iter().find(|shape| shape.letter() == letter)
+    }
+}
+
+impl fmt::Display for InsertShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.nose_angle() {
+            Some(angle) => write!(f, "{} ({}°)", self.letter(), angle),
+            None => write!(f, "{} (round)", self.letter()),
+        }
+    }
+}
+
+/// Clearance angle under the cutting edge, second letter of the code.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum ClearanceAngle {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    #[default]
+    N,
+    P,
+}
+
+impl ClearanceAngle {
+    pub fn letter(&self) -> char {
+        match self {
+            ClearanceAngle::A => 'A',
+            ClearanceAngle::B => 'B',
+            ClearanceAngle::C => 'C',
+            ClearanceAngle::D => 'D',
+            ClearanceAngle::E => 'E',
+            ClearanceAngle::F => 'F',
+            ClearanceAngle::G => 'G',
+            ClearanceAngle::N => 'N',
+            ClearanceAngle::P => 'P',
+        }
+    }
+
+    /// [°]
+    pub fn angle(&self) -> f32 {
+        match self {
+            ClearanceAngle::A => 3.0,
+            ClearanceAngle::B => 5.0,
+            ClearanceAngle::C => 7.0,
+            ClearanceAngle::D => 15.0,
+            ClearanceAngle::E => 20.0,
+            ClearanceAngle::F => 25.0,
+            ClearanceAngle::G => 30.0,
+            ClearanceAngle::N => 0.0,
+            ClearanceAngle::P => 11.0,
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        ClearanceAngle::iter().find(|clearance| clearance.letter() == letter)
+    }
+}
+
+impl fmt::Display for ClearanceAngle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}°)", self.letter(), self.angle())
+    }
+}
+
+/// Tolerance classes, third letter of the code.
+pub const TOLERANCE_CLASSES: [char; 12] =
+    ['A', 'F', 'C', 'H', 'E', 'G', 'J', 'K', 'L', 'M', 'N', 'U'];
+
+/// Fixing hole and chipbreaker types, fourth letter of the code.
+pub const INSERT_TYPES: [char; 15] = [
+    'A', 'B', 'C', 'F', 'G', 'H', 'J', 'M', 'N', 'Q', 'R', 'T', 'U', 'W', 'X',
+];
+
+/// Thickness codes and the thickness they stand for [mm].
+pub const THICKNESSES: [(&str, f32); 11] = [
+    ("01", 1.59),
+    ("T1", 1.98),
+    ("02", 2.38),
+    ("T2", 2.78),
+    ("03", 3.18),
+    ("T3", 3.97),
+    ("04", 4.76),
+    ("05", 5.56),
+    ("06", 6.35),
+    ("07", 7.94),
+    ("09", 9.52),
+];
+
+/// Nose radii offered when editing field by field [mm].
+const NOSE_RADII: [f32; 8] = [0.0, 0.1, 0.2, 0.4, 0.8, 1.2, 1.6, 2.4];
+
+/// A turning insert described by its ISO 1832 code, e.g. CNMG 120408-PM.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct IsoInsert {
+    pub name: String,
+    pub shape: InsertShape,
+    pub clearance: ClearanceAngle,
+    pub tolerance: char,
+    pub insert_type: char,
+    pub edge_length: u32, // Cutting edge length [mm] as in the code, the diameter for round
+    pub thickness: String, // Thickness code, see `THICKNESSES`
+    pub nose_radius: f32, // [mm], 0 for sharp corners and round inserts
+    pub chipbreaker: String, // Manufacturer's chipbreaker after the dash
+    pub color: Color32,
+    #[serde(skip)]
+    pub code_input: String,
+    #[serde(skip)]
+    pub code_error: Option<String>,
+}
+
+impl Default for IsoInsert {
+    fn default() -> Self {
+        Self {
+            name: "CNMG 120408-PM".to_string(),
+            shape: InsertShape::C,
+            clearance: ClearanceAngle::N,
+            tolerance: 'M',
+            insert_type: 'G',
+            edge_length: 12,
+            thickness: "04".to_string(),
+            nose_radius: 0.8,
+            chipbreaker: "PM".to_string(),
+            color: Color32::GREEN,
+            code_input: String::new(),
+            code_error: None,
+        }
+    }
+}
+
+impl IsoInsert {
+    /// Parses an ISO 1832 code such as `CNMG 120408-PM` or `cnmg120408pm`. Spaces are ignored,
+    /// whatever follows the size digits is taken as the chipbreaker.
+    pub fn from_code(code: &str) -> Result<IsoInsert, String> {
+        let chars: Vec<char> = code
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if chars.len() < 10 {
+            return Err(format!(
+                "{} is too short, expected four letters and six size digits",
+                code.trim()
+            ));
+        }
+        let shape = InsertShape::from_letter(chars[0])
+            .ok_or(format!("Unknown insert shape {}", chars[0]))?;
+        let clearance = ClearanceAngle::from_letter(chars[1])
+            .ok_or(format!("Unknown clearance angle {}", chars[1]))?;
+        let tolerance = chars[2];
+        if !TOLERANCE_CLASSES.contains(&tolerance) {
+            return Err(format!("Unknown tolerance class {}", tolerance));
+        }
+        let insert_type = chars[3];
+        if !INSERT_TYPES.contains(&insert_type) {
+            return Err(format!("Unknown insert type {}", insert_type));
+        }
+        let pair = |start: usize| chars[start..start + 2].iter().collect::<String>();
+        let edge_length = pair(4)
+            .parse::<u32>()
+            .ok()
+            .filter(|length| *length > 0)
+            .ok_or(format!("Cutting edge length {} is not a size", pair(4)))?;
+        let thickness = pair(6);
+        if !THICKNESSES.iter().any(|(code, _)| *code == thickness) {
+            return Err(format!("Unknown thickness code {}", thickness));
+        }
+        let radius = pair(8);
+        let nose_radius = match (shape, radius.as_str()) {
+            (InsertShape::R, "M0" | "00") => 0.0,
+            (InsertShape::R, _) => {
+                return Err(format!(
+                    "Round inserts have no corner, got radius {}",
+                    radius
+                ))
+            }
+            (_, "M0") => return Err("Only round inserts use the radius code M0".to_string()),
+            (_, _) => radius
+                .parse::<u32>()
+                .map(|tenths| tenths as f32 / 10.0)
+                .map_err(|_| format!("Nose radius {} is not a size", radius))?,
+        };
+        let chipbreaker: String = chars[10..].iter().collect();
+        let chipbreaker = chipbreaker.trim_start_matches('-').to_string();
+        let mut insert = IsoInsert {
+            shape,
+            clearance,
+            tolerance,
+            insert_type,
+            edge_length,
+            thickness,
+            nose_radius,
+            chipbreaker,
+            ..IsoInsert::default()
+        };
+        insert.name = insert.code();
+        Ok(insert)
+    }
+
+    pub fn code(&self) -> String {
+        let radius = match self.shape {
+            InsertShape::R => "M0".to_string(),
+            _ => format!("{:02}", (self.nose_radius * 10.0).round() as u32),
+        };
+        let mut code = format!(
+            "{}{}{}{} {:02}{}{}",
+            self.shape.letter(),
+            self.clearance.letter(),
+            self.tolerance,
+            self.insert_type,
+            self.edge_length,
+            self.thickness,
+            radius
+        );
+        if !self.chipbreaker.is_empty() {
+            code.push('-');
+            code.push_str(&self.chipbreaker);
+        }
+        code
+    }
+
+    /// Thickness [mm] the thickness code stands for.
+    pub fn thickness_mm(&self) -> Option<f32> {
+        THICKNESSES
+            .iter()
+            .find(|(code, _)| *code == self.thickness)
+            .map(|(_, thickness)| *thickness)
+    }
+
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        let code_before = self.code();
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("ISO code");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.code_input)
+                .on_hover_text("For example CNMG 120408-PM");
+            if ui.button("Apply").clicked() {
+                match IsoInsert::from_code(&self.code_input) {
+                    Ok(parsed) => {
+                        *self = IsoInsert {
+                            color: self.color,
+                            code_input: String::new(),
+                            ..parsed
+                        }
+                    }
+                    Err(error) => self.code_error = Some(error),
+                }
+            }
+        });
+        if let Some(error) = &self.code_error {
+            ui.colored_label(Color32::RED, error);
+        }
+        ui.separator();
+        egui::Grid::new("iso_insert_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Shape:");
+                egui::ComboBox::from_id_source("insert_shape")
+                    .selected_text(self.shape.to_string())
+                    .show_ui(ui, |ui| {
+                        for shape in InsertShape::iter() {
+                            ui.selectable_value(&mut self.shape, shape, shape.to_string());
+                        }
+                    });
+                ui.end_row();
+                ui.label("Clearance:");
+                egui::ComboBox::from_id_source("insert_clearance")
+                    .selected_text(self.clearance.to_string())
+                    .show_ui(ui, |ui| {
+                        for clearance in ClearanceAngle::iter() {
+                            ui.selectable_value(
+                                &mut self.clearance,
+                                clearance,
+                                clearance.to_string(),
+                            );
+                        }
+                    });
+                ui.end_row();
+                ui.label("Tolerance class:");
+                letter_combo(
+                    ui,
+                    "insert_tolerance",
+                    &mut self.tolerance,
+                    &TOLERANCE_CLASSES,
+                );
+                ui.end_row();
+                ui.label("Type:");
+                letter_combo(ui, "insert_type", &mut self.insert_type, &INSERT_TYPES);
+                ui.end_row();
+                ui.label(match self.shape {
+                    InsertShape::R => "Diameter:",
+                    _ => "Cutting edge length:",
+                });
+                ui.add(egui::DragValue::new(&mut self.edge_length).clamp_range(1..=99));
+                ui.end_row();
+                ui.label("Thickness:");
+                egui::ComboBox::from_id_source("insert_thickness")
+                    .selected_text(&self.thickness)
+                    .show_ui(ui, |ui| {
+                        for (code, thickness) in THICKNESSES {
+                            ui.selectable_value(
+                                &mut self.thickness,
+                                code.to_string(),
+                                format!("{} ({} mm)", code, thickness),
+                            );
+                        }
+                    });
+                ui.end_row();
+                if self.shape != InsertShape::R {
+                    ui.label("Nose radius:");
+                    egui::ComboBox::from_id_source("insert_nose_radius")
+                        .selected_text(format!("{}", self.nose_radius))
+                        .show_ui(ui, |ui| {
+                            for radius in NOSE_RADII {
+                                ui.selectable_value(
+                                    &mut self.nose_radius,
+                                    radius,
+                                    radius.to_string(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                }
+                ui.label("Chipbreaker:");
+                ui.text_edit_singleline(&mut self.chipbreaker);
+                ui.end_row();
+            });
+        if self.shape == InsertShape::R {
+            self.nose_radius = 0.0;
+        }
+        // A name left at the code follows the code
+        if self.name == code_before {
+            self.name = self.code();
+        }
+        ui.label(format!("Code: {}", self.code()));
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(self.code()).on_hover_text(format!(
+                "Clearance {}°, thickness {} mm",
+                self.clearance.angle(),
+                self.thickness_mm().unwrap_or_default()
+            ));
+            match self.shape.nose_angle() {
+                Some(angle) => ui.label(format!("{}° R{}", angle, self.nose_radius)),
+                None => ui.label("Round"),
+            };
+        });
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::LatheInsert
+    }
+
+    pub fn get_degree(&self) -> f32 {
+        self.shape.nose_angle().unwrap_or_default()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        format!("{} insert", self.shape.letter())
+    }
+}
+
+fn letter_combo(ui: &mut egui::Ui, id: &str, letter: &mut char, letters: &[char]) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(letter.to_string())
+        .show_ui(ui, |ui| {
+            for option in letters {
+                ui.selectable_value(letter, *option, option.to_string());
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cnmg_120408_pm() {
+        let insert = IsoInsert::from_code("cnmg120408pm").unwrap();
+        assert_eq!(insert.shape, InsertShape::C);
+        assert_eq!(insert.clearance, ClearanceAngle::N);
+        assert_eq!((insert.tolerance, insert.insert_type), ('M', 'G'));
+        assert_eq!(insert.edge_length, 12);
+        assert_eq!(insert.thickness, "04");
+        assert_eq!(insert.nose_radius, 0.8);
+        assert_eq!(insert.chipbreaker, "PM");
+        assert_eq!(insert.code(), "CNMG 120408-PM");
+        assert_eq!(IsoInsert::from_code(&insert.code()), Ok(insert));
+    }
+
+    #[test]
+    fn parses_round_rcmt_1204m0() {
+        let insert = IsoInsert::from_code("RCMT 1204M0").unwrap();
+        assert_eq!(insert.shape, InsertShape::R);
+        assert_eq!(insert.clearance, ClearanceAngle::C);
+        assert_eq!(insert.edge_length, 12);
+        assert_eq!(insert.nose_radius, 0.0);
+        assert!(insert.chipbreaker.is_empty());
+        assert_eq!(insert.code(), "RCMT 1204M0");
+        assert_eq!(IsoInsert::from_code(&insert.code()), Ok(insert));
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        assert!(IsoInsert::from_code("CNMG 1204").is_err());
+        assert!(IsoInsert::from_code("XNMG 120408").is_err());
+        assert!(IsoInsert::from_code("CNMG 1204M0").is_err());
+        assert!(IsoInsert::from_code("CNMG 12ZZ08").is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::tool::ToolCategory;
+use crate::geometry::ToolGeometry;
+use crate::tool::{Oversize, ToolCategory};
 use egui::Color32;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
@@ -6,6 +7,10 @@ pub struct Mill {
     pub name: String,
     pub diameter: f32,
     pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+    #[serde(default)]
+    pub geometry: ToolGeometry,
 }
 
 impl Default for Mill {
@@ -13,7 +18,9 @@ impl Default for Mill {
         Self {
             name: "Mill".to_string(),
             diameter: 10.0,
+            oversize: Oversize::default(),
             color: Color32::BLUE,
+            geometry: ToolGeometry::default(),
         }
     }
 }
@@ -26,6 +33,8 @@ impl Mill {
             ui.text_edit_singleline(&mut self.name);
         });
         ui.add(egui::Slider::new(&mut self.diameter, 0.001..=200.0).text("Diameter"));
+        self.geometry.edit(ui, self.diameter);
+        self.oversize.edit(ui);
         ui.separator();
         *add = ui.button("Add").clicked()
     }
@@ -40,6 +49,8 @@ impl Mill {
                 ui.label("Diameter:");
                 ui.label(&self.diameter.to_string());
             });
+            self.geometry.display(ui, self.diameter);
+            self.oversize.display(ui);
         });
     }
 
@@ -51,6 +62,10 @@ impl Mill {
         self.diameter
     }
 
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
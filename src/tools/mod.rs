@@ -1,4 +1,12 @@
+pub mod chamfer_mill;
 pub mod drill;
+pub mod end_mill;
+pub mod face_mill;
+pub mod geometry;
+pub mod iso_insert;
 pub mod mill;
+pub mod reamer;
+pub mod tap;
+pub mod thread_mill;
 pub mod tool;
 pub mod trigoninsert;
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+/// ISO tolerance class of the bore a reamer produces.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum ToleranceClass {
+    H6,
+    #[default]
+    H7,
+    H8,
+    H9,
+    H11,
+}
+
+impl fmt::Display for ToleranceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToleranceClass::H6 => write!(f, "H6"),
+            ToleranceClass::H7 => write!(f, "H7"),
+            ToleranceClass::H8 => write!(f, "H8"),
+            ToleranceClass::H9 => write!(f, "H9"),
+            ToleranceClass::H11 => write!(f, "H11"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct Reamer {
+    pub name: String,
+    pub diameter: f32,
+    pub tolerance: ToleranceClass,
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+}
+
+impl Default for Reamer {
+    fn default() -> Self {
+        Self {
+            name: "Reamer".to_string(),
+            diameter: 10.0,
+            tolerance: ToleranceClass::H7,
+            color: Color32::from_rgb(160, 80, 200),
+            oversize: Oversize::default(),
+        }
+    }
+}
+
+impl Reamer {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 0.5..=100.0).text("Diameter"));
+        egui::ComboBox::from_label("Tolerance class")
+            .selected_text(self.tolerance.to_string())
+            .show_ui(ui, |ui| {
+                for tolerance in ToleranceClass::iter() {
+                    ui.selectable_value(&mut self.tolerance, tolerance, tolerance.to_string());
+                }
+            });
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(format!("Ø{} {}", self.diameter, self.tolerance));
+            self.oversize.display(ui);
+        });
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        "Reamer".to_string()
+    }
+}
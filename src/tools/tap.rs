@@ -0,0 +1,108 @@
+use std::fmt;
+
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TapKind {
+    #[default]
+    Cutting,
+    Forming, // Cold forms the thread, needs a larger core hole
+}
+
+impl fmt::Display for TapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TapKind::Cutting => write!(f, "Cutting"),
+            TapKind::Forming => write!(f, "Forming"),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct Tap {
+    pub name: String,
+    pub thread: String, // Designation such as M8 or G1/4
+    pub diameter: f32,  // Nominal thread diameter
+    pub pitch: f32,
+    pub kind: TapKind,
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+}
+
+impl Default for Tap {
+    fn default() -> Self {
+        Self {
+            name: "Tap".to_string(),
+            thread: "M8".to_string(),
+            diameter: 8.0,
+            pitch: 1.25,
+            kind: TapKind::Cutting,
+            color: Color32::from_rgb(200, 120, 0),
+            oversize: Oversize::default(),
+        }
+    }
+}
+
+impl Tap {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Thread");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.thread);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 0.5..=100.0).text("Diameter"));
+        ui.add(egui::Slider::new(&mut self.pitch, 0.1..=6.0).text("Pitch"));
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.kind, TapKind::Cutting, "Cutting");
+            ui.radio_value(&mut self.kind, TapKind::Forming, "Forming");
+        });
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(format!("{}x{}", self.thread, self.pitch));
+            ui.label(self.kind.to_string());
+            self.oversize.display(ui);
+        });
+    }
+
+    /// Core hole diameter to drill before tapping.
+    pub fn core_hole_diameter(&self) -> f32 {
+        match self.kind {
+            TapKind::Cutting => self.diameter - self.pitch,
+            TapKind::Forming => self.diameter - self.pitch / 2.0,
+        }
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        "Tap".to_string()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::tool::{Oversize, ToolCategory};
+use egui::Color32;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ThreadMill {
+    pub name: String,
+    pub thread: String, // Smallest thread the tool cuts, such as M8
+    pub diameter: f32,  // Cutting diameter
+    pub pitch: f32,
+    pub color: Color32,
+    #[serde(default)]
+    pub oversize: Oversize,
+}
+
+impl Default for ThreadMill {
+    fn default() -> Self {
+        Self {
+            name: "Thread mill".to_string(),
+            thread: "M8".to_string(),
+            diameter: 6.0,
+            pitch: 1.25,
+            color: Color32::from_rgb(220, 180, 0),
+            oversize: Oversize::default(),
+        }
+    }
+}
+
+impl ThreadMill {
+    pub fn tool_edit(&mut self, ui: &mut egui::Ui, add: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Thread");
+            ui.separator();
+            ui.text_edit_singleline(&mut self.thread);
+        });
+        ui.add(egui::Slider::new(&mut self.diameter, 0.5..=100.0).text("Diameter"));
+        ui.add(egui::Slider::new(&mut self.pitch, 0.1..=6.0).text("Pitch"));
+        self.oversize.edit(ui);
+        ui.separator();
+        *add = ui.button("Add").clicked()
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(self.color, &self.name);
+            ui.separator();
+            ui.label(format!("Ø{}", self.diameter));
+            ui.label(format!("{}x{}", self.thread, self.pitch));
+            self.oversize.display(ui);
+        });
+    }
+
+    pub fn get_category(&self) -> ToolCategory {
+        ToolCategory::Rotating
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        self.oversize.clone()
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.color = color;
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.color
+    }
+
+    pub fn get_type(&self) -> String {
+        "Thread mill".to_string()
+    }
+}
@@ -1,9 +1,17 @@
 use egui::Color32;
 
 use crate::app::*;
+use crate::chamfer_mill::ChamferMill;
 use crate::drill::Drill;
+use crate::end_mill::EndMill;
+use crate::face_mill::FaceMill;
+use crate::geometry::ToolGeometry;
+use crate::iso_insert::IsoInsert;
 use crate::mill::Mill;
+use crate::reamer::Reamer;
 use crate::resources::*;
+use crate::tap::Tap;
+use crate::thread_mill::ThreadMill;
 use crate::trigoninsert::TrigonInsert;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
@@ -15,11 +23,50 @@ pub enum ToolCategory {
     Empty,
 }
 
+/// Pockets an oversized tool blocks on each side of its own in the magazine.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Oversize {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Oversize {
+    pub fn is_oversized(&self) -> bool {
+        self.left > 0 || self.right > 0
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Blocked pockets:")
+                .on_hover_text("Neighbouring pockets an oversized tool blocks");
+            ui.label("left");
+            ui.add(egui::DragValue::new(&mut self.left).clamp_range(0..=5));
+            ui.label("right");
+            ui.add(egui::DragValue::new(&mut self.right).clamp_range(0..=5));
+        });
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        if self.is_oversized() {
+            ui.separator();
+            ui.label(format!("Oversize ←{} {}→", self.left, self.right))
+                .on_hover_text("Blocks neighbouring pockets");
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub enum Tool {
     Drill(Drill),
     Mill(Mill),
+    EndMill(EndMill),
+    ChamferMill(ChamferMill),
+    FaceMill(FaceMill),
+    Tap(Tap),
+    Reamer(Reamer),
+    ThreadMill(ThreadMill),
     TrigonInsert(TrigonInsert),
+    IsoInsert(IsoInsert),
 }
 
 impl Tool {
@@ -27,7 +74,14 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.tool_edit(ui, add),
             Tool::Mill(mill) => mill.tool_edit(ui, add),
+            Tool::EndMill(end_mill) => end_mill.tool_edit(ui, add),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.tool_edit(ui, add),
+            Tool::FaceMill(face_mill) => face_mill.tool_edit(ui, add),
+            Tool::Tap(tap) => tap.tool_edit(ui, add),
+            Tool::Reamer(reamer) => reamer.tool_edit(ui, add),
+            Tool::ThreadMill(thread_mill) => thread_mill.tool_edit(ui, add),
             Tool::TrigonInsert(trigon_insert) => trigon_insert.tool_edit(ui, add),
+            Tool::IsoInsert(iso_insert) => iso_insert.tool_edit(ui, add),
         }
     }
 
@@ -35,15 +89,28 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.display(ui),
             Tool::Mill(mill) => mill.display(ui),
+            Tool::EndMill(end_mill) => end_mill.display(ui),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.display(ui),
+            Tool::FaceMill(face_mill) => face_mill.display(ui),
+            Tool::Tap(tap) => tap.display(ui),
+            Tool::Reamer(reamer) => reamer.display(ui),
+            Tool::ThreadMill(thread_mill) => thread_mill.display(ui),
             Tool::TrigonInsert(trigon_insert) => trigon_insert.display(ui),
+            Tool::IsoInsert(iso_insert) => iso_insert.display(ui),
         }
     }
 
     pub fn get_category(&self) -> ToolCategory {
         match self {
-            Tool::Drill(_) => ToolCategory::Rotating,
-            Tool::Mill(_) => ToolCategory::Rotating,
-            Tool::TrigonInsert(_) => ToolCategory::LatheInsert,
+            Tool::Drill(_)
+            | Tool::Mill(_)
+            | Tool::EndMill(_)
+            | Tool::ChamferMill(_)
+            | Tool::FaceMill(_)
+            | Tool::Tap(_)
+            | Tool::Reamer(_)
+            | Tool::ThreadMill(_) => ToolCategory::Rotating,
+            Tool::TrigonInsert(_) | Tool::IsoInsert(_) => ToolCategory::LatheInsert,
         }
     }
 
@@ -51,15 +118,86 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.diameter,
             Tool::Mill(mill) => mill.diameter,
-            Tool::TrigonInsert(_) => 0.0,
+            Tool::EndMill(end_mill) => end_mill.diameter,
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.diameter,
+            Tool::FaceMill(face_mill) => face_mill.diameter,
+            Tool::Tap(tap) => tap.diameter,
+            Tool::Reamer(reamer) => reamer.diameter,
+            Tool::ThreadMill(thread_mill) => thread_mill.diameter,
+            Tool::TrigonInsert(_) | Tool::IsoInsert(_) => 0.0,
+        }
+    }
+
+    /// Diameter clamped by the holder. Shank tools without a geometry are taken to have a
+    /// shank of their cutting diameter. Face mills sit on an arbor and inserts have no shank.
+    pub fn get_shank_diameter(&self) -> Option<f32> {
+        match self {
+            Tool::FaceMill(_) | Tool::TrigonInsert(_) | Tool::IsoInsert(_) => None,
+            tool => Some(match tool.get_geometry() {
+                Some(geometry) => geometry.shank_diameter(tool.get_diameter()),
+                None => tool.get_diameter(),
+            }),
+        }
+    }
+
+    /// Flutes, lengths, material and coating of solid drills and mills.
+    pub fn get_geometry(&self) -> Option<&ToolGeometry> {
+        match self {
+            Tool::Drill(drill) => Some(&drill.geometry),
+            Tool::Mill(mill) => Some(&mill.geometry),
+            Tool::EndMill(end_mill) => Some(&end_mill.geometry),
+            _ => None,
+        }
+    }
+
+    /// Size that orders tools of the same type and diameter: corner radius, chamfer angle,
+    /// insert count, pitch or nose radius.
+    pub fn get_sort_detail(&self) -> f32 {
+        match self {
+            Tool::EndMill(end_mill) => end_mill.get_corner_radius(),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.angle,
+            Tool::FaceMill(face_mill) => face_mill.insert_count as f32,
+            Tool::Tap(tap) => tap.pitch,
+            Tool::ThreadMill(thread_mill) => thread_mill.pitch,
+            Tool::TrigonInsert(trigon_insert) => trigon_insert.degree,
+            Tool::IsoInsert(iso_insert) => iso_insert.nose_radius,
+            Tool::Drill(_) | Tool::Mill(_) | Tool::Reamer(_) => 0.0,
+        }
+    }
+
+    pub fn set_diameter(&mut self, diameter: f32) {
+        match self {
+            Tool::Drill(drill) => drill.diameter = diameter,
+            Tool::Mill(mill) => mill.diameter = diameter,
+            Tool::EndMill(end_mill) => end_mill.diameter = diameter,
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.diameter = diameter,
+            Tool::FaceMill(face_mill) => face_mill.diameter = diameter,
+            Tool::Tap(tap) => tap.diameter = diameter,
+            Tool::Reamer(reamer) => reamer.diameter = diameter,
+            Tool::ThreadMill(thread_mill) => thread_mill.diameter = diameter,
+            Tool::TrigonInsert(_) | Tool::IsoInsert(_) => {}
+        }
+    }
+
+    pub fn get_oversize(&self) -> Oversize {
+        match self {
+            Tool::Drill(drill) => drill.get_oversize(),
+            Tool::Mill(mill) => mill.get_oversize(),
+            Tool::EndMill(end_mill) => end_mill.get_oversize(),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.get_oversize(),
+            Tool::FaceMill(face_mill) => face_mill.get_oversize(),
+            Tool::Tap(tap) => tap.get_oversize(),
+            Tool::Reamer(reamer) => reamer.get_oversize(),
+            Tool::ThreadMill(thread_mill) => thread_mill.get_oversize(),
+            Tool::TrigonInsert(_) | Tool::IsoInsert(_) => Oversize::default(),
         }
     }
 
     pub fn get_degree(&self) -> f32 {
         match self {
-            Tool::Drill(_) => 0.0,
-            Tool::Mill(_) => 0.0,
             Tool::TrigonInsert(trigon_insert) => trigon_insert.degree,
+            Tool::IsoInsert(iso_insert) => iso_insert.get_degree(),
+            _ => 0.0,
         }
     }
 
@@ -67,7 +205,29 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.name.clone(),
             Tool::Mill(mill) => mill.name.clone(),
+            Tool::EndMill(end_mill) => end_mill.name.clone(),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.name.clone(),
+            Tool::FaceMill(face_mill) => face_mill.name.clone(),
+            Tool::Tap(tap) => tap.name.clone(),
+            Tool::Reamer(reamer) => reamer.name.clone(),
+            Tool::ThreadMill(thread_mill) => thread_mill.name.clone(),
             Tool::TrigonInsert(trigon_insert) => trigon_insert.name.clone(),
+            Tool::IsoInsert(iso_insert) => iso_insert.name.clone(),
+        }
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            Tool::Drill(drill) => drill.name = name,
+            Tool::Mill(mill) => mill.name = name,
+            Tool::EndMill(end_mill) => end_mill.name = name,
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.name = name,
+            Tool::FaceMill(face_mill) => face_mill.name = name,
+            Tool::Tap(tap) => tap.name = name,
+            Tool::Reamer(reamer) => reamer.name = name,
+            Tool::ThreadMill(thread_mill) => thread_mill.name = name,
+            Tool::TrigonInsert(trigon_insert) => trigon_insert.name = name,
+            Tool::IsoInsert(iso_insert) => iso_insert.name = name,
         }
     }
 
@@ -75,7 +235,14 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.set_color(color),
             Tool::Mill(mill) => mill.set_color(color),
+            Tool::EndMill(end_mill) => end_mill.set_color(color),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.set_color(color),
+            Tool::FaceMill(face_mill) => face_mill.set_color(color),
+            Tool::Tap(tap) => tap.set_color(color),
+            Tool::Reamer(reamer) => reamer.set_color(color),
+            Tool::ThreadMill(thread_mill) => thread_mill.set_color(color),
             Tool::TrigonInsert(trigon) => trigon.set_color(color),
+            Tool::IsoInsert(iso_insert) => iso_insert.set_color(color),
         }
     }
 
@@ -83,7 +250,14 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.get_color(),
             Tool::Mill(mill) => mill.get_color(),
+            Tool::EndMill(end_mill) => end_mill.get_color(),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.get_color(),
+            Tool::FaceMill(face_mill) => face_mill.get_color(),
+            Tool::Tap(tap) => tap.get_color(),
+            Tool::Reamer(reamer) => reamer.get_color(),
+            Tool::ThreadMill(thread_mill) => thread_mill.get_color(),
             Tool::TrigonInsert(trigon) => trigon.color,
+            Tool::IsoInsert(iso_insert) => iso_insert.get_color(),
         }
     }
 
@@ -91,7 +265,14 @@ impl Tool {
         match self {
             Tool::Drill(drill) => drill.get_type(),
             Tool::Mill(mill) => mill.get_type(),
+            Tool::EndMill(end_mill) => end_mill.get_type(),
+            Tool::ChamferMill(chamfer_mill) => chamfer_mill.get_type(),
+            Tool::FaceMill(face_mill) => face_mill.get_type(),
+            Tool::Tap(tap) => tap.get_type(),
+            Tool::Reamer(reamer) => reamer.get_type(),
+            Tool::ThreadMill(thread_mill) => thread_mill.get_type(),
             Tool::TrigonInsert(trigon) => trigon.get_type(),
+            Tool::IsoInsert(iso_insert) => iso_insert.get_type(),
         }
     }
 }
@@ -129,9 +310,7 @@ pub fn add_rotating_tool(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_to
         .rotating_tools
         .get(app.selections.selected_rotating_tool_index)
     {
-        Some(Tool::Drill(drill)) => drill.get_type(),
-        Some(Tool::Mill(mill)) => mill.get_type(),
-        Some(Tool::TrigonInsert(trigon_insert)) => trigon_insert.get_type(),
+        Some(tool) => tool.get_type(),
         None => String::from("Select tool"),
     };
 
@@ -140,11 +319,7 @@ pub fn add_rotating_tool(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_to
         .show_ui(ui, |ui| {
             // get index to selected tool
             for (i, tool) in app.gui_singletons.rotating_tools.iter().enumerate() {
-                let label = match tool {
-                    Tool::Drill(drill) => drill.get_type(),
-                    Tool::Mill(mill) => mill.get_type(),
-                    Tool::TrigonInsert(trigon_insert) => trigon_insert.get_type(),
-                };
+                let label = tool.get_type();
                 if ui
                     .selectable_label(app.selections.selected_rotating_tool_index == i, label)
                     .clicked()
@@ -175,9 +350,7 @@ pub fn add_insert_tool(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_tool
         .insert_tools
         .get(app.selections.selected_rotating_tool_index)
     {
-        Some(Tool::Drill(drill)) => drill.name.clone(),
-        Some(Tool::Mill(mill)) => mill.name.clone(),
-        Some(Tool::TrigonInsert(trigon_insert)) => trigon_insert.name.clone(),
+        Some(tool) => tool.get_name(),
         None => String::from("Select tool"),
     };
     egui::ComboBox::from_label("Select type of tool")
@@ -185,11 +358,7 @@ pub fn add_insert_tool(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_tool
         .show_ui(ui, |ui| {
             // get index to selected tool
             for (i, tool) in app.gui_singletons.insert_tools.iter().enumerate() {
-                let label = match tool {
-                    Tool::Drill(drill) => drill.name.clone(),
-                    Tool::Mill(mill) => mill.name.clone(),
-                    Tool::TrigonInsert(trigon_insert) => trigon_insert.name.clone(),
-                };
+                let label = tool.get_name();
                 if ui
                     .selectable_label(app.selections.selected_insert_tool_index == i, label)
                     .clicked()
@@ -199,11 +368,24 @@ pub fn add_insert_tool(app: &mut ManagingApp, ui: &mut egui::Ui, should_add_tool
             }
         });
     ui.separator();
-    modify_tool(
-        &mut app.gui_singletons.insert_tools[app.selections.selected_insert_tool_index],
-        ui,
-        should_add_tool,
-    );
+    let templates = &mut app.gui_singletons.insert_tools;
+    let index = app.selections.selected_insert_tool_index;
+    let original = templates[index].clone();
+    modify_tool(&mut templates[index], ui, should_add_tool);
+    // An insert edited into another shape moves to the template of that shape
+    let edited_type = templates[index].get_type();
+    if edited_type != original.get_type() {
+        if let Some(other) = templates
+            .iter()
+            .position(|template| template.get_type() == edited_type)
+            .filter(|other| *other != index)
+        {
+            let color = templates[other].get_color();
+            templates[other] = std::mem::replace(&mut templates[index], original);
+            templates[other].set_color(color);
+            app.selections.selected_insert_tool_index = other;
+        }
+    }
     if *should_add_tool {
         let cloned_tool =
             app.gui_singletons.insert_tools[app.selections.selected_insert_tool_index].clone();
@@ -0,0 +1,229 @@
+use crate::compatibility::*;
+use crate::resources::SortBy;
+use crate::slot::*;
+use crate::{reset_states, ManagingApp, MoveStates};
+
+/// A pocket anywhere in the app.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlotAddress {
+    pub machine: usize,
+    pub magazine: usize,
+    pub pocket: usize,
+}
+
+/// Address of a pocket in the displayed magazine.
+pub fn current_address(app: &ManagingApp, pocket: usize) -> Option<SlotAddress> {
+    let machine = app.selections.machine?;
+    Some(SlotAddress {
+        machine,
+        magazine: app.machines[machine].current_magazine?,
+        pocket,
+    })
+}
+
+/// Exchanges the contents of two pockets, which may lie in different magazines or machines.
+/// Offsets and comments go with the contents. The exchange is refused when a tool lands on a
+/// machine that cannot take it or in a blocked pocket, and reviewed like a mount otherwise.
+pub fn transfer_slot(app: &mut ManagingApp, from: SlotAddress, to: SlotAddress) {
+    if app.move_selections.move_refusal.is_some() {
+        return;
+    }
+    if from == to {
+        reset_states(app);
+        return;
+    }
+    let same_magazine = from.machine == to.machine && from.magazine == to.magazine;
+    let mut source = app.machines[from.machine].magazines[from.magazine].clone();
+    let mut target = if same_magazine {
+        None
+    } else {
+        Some(app.machines[to.machine].magazines[to.magazine].clone())
+    };
+    match &mut target {
+        None => source.swap_contents(from.pocket, to.pocket),
+        Some(target) => {
+            source.contents[from.pocket].exchange_contents(&mut target.contents[to.pocket]);
+            source.refresh_blocked_pockets();
+            target.refresh_blocked_pockets();
+        }
+    }
+
+    let sides = [(from, &source), (to, target.as_ref().unwrap_or(&source))];
+    let refusal = sides.iter().find_map(|(address, magazine)| {
+        let slot = &magazine.contents[address.pocket];
+        let machine = &app.machines[address.machine];
+        if slot.is_loaded() && slot.state == SlotState::Blocked {
+            return Some(format!(
+                "Slot {} of {} is blocked",
+                slot.pocket, magazine.name
+            ));
+        }
+        let tool = slot.tool.as_ref()?;
+        (!machine.machine_type.accepts_tool(tool)).then(|| {
+            format!(
+                "{} is a {} and cannot be mounted on {}",
+                tool.get_name(),
+                tool.get_type(),
+                machine.name
+            )
+        })
+    });
+    // Conflicts elsewhere in the magazines predate the swap and do not stop it
+    let oversize_conflict = || {
+        sides
+            .iter()
+            .find_map(|(address, magazine)| magazine.oversize_conflict_at(address.pocket))
+    };
+    if let Some(refusal) = refusal.or_else(oversize_conflict) {
+        app.move_selections.move_refusal = Some(refusal);
+        return;
+    }
+    let violated: Vec<(SlotAddress, Vec<String>)> = sides
+        .iter()
+        .map(|(address, magazine)| {
+            let spindle = app.machines[address.machine].spindle_interface;
+            (
+                *address,
+                mount_violations(&magazine.contents[address.pocket], spindle),
+            )
+        })
+        .filter(|(_, violations)| !violations.is_empty())
+        .collect();
+    let all_violations = violated
+        .iter()
+        .flat_map(|(address, violations)| {
+            violations
+                .iter()
+                .map(move |violation| format!("Slot {}: {}", address.pocket, violation))
+        })
+        .collect();
+    match review_violations(&mut app.move_selections, all_violations) {
+        MountReview::Blocked => return,
+        MountReview::Allowed => {}
+        MountReview::Overridden(note) => {
+            for (address, _) in violated {
+                let magazine = match &mut target {
+                    Some(target) if address == to => target,
+                    _ => &mut source,
+                };
+                record_override(&mut magazine.contents[address.pocket], note.clone());
+            }
+        }
+    }
+
+    app.machines[from.machine].magazines[from.magazine] = source;
+    if let Some(target) = target {
+        app.machines[to.machine].magazines[to.magazine] = target;
+    }
+    if let Some(machine) = app.selections.machine {
+        if let Some(current) = app.machines[machine].current_magazine {
+            // The rebuilt table lists every pocket in order, so the filter and sort go too
+            app.display_magazine = app.machines[machine].magazines[current].clone();
+            app.gui_singletons.tool_filter = None;
+            app.gui_singletons.material_filter = None;
+            app.gui_singletons.sort_by = SortBy::Slot;
+        }
+    }
+    reset_states(app);
+}
+
+/// Swaps two pockets of the displayed magazine, as dragged in the magazine table.
+pub fn swap_slots(app: &mut ManagingApp) {
+    let (Some(from), Some(to)) = (
+        app.move_selections.selected_slot_index_magazine,
+        app.move_selections.swap_target_index_magazine,
+    ) else {
+        return;
+    };
+    if let (Some(from), Some(to)) = (current_address(app, from), current_address(app, to)) {
+        transfer_slot(app, from, to);
+    }
+}
+
+/// Starts moving the contents of a pocket of the displayed magazine elsewhere.
+pub fn start_transfer(app: &mut ManagingApp, pocket: usize) {
+    let Some(address) = current_address(app, pocket) else {
+        return;
+    };
+    app.move_selections.selected_slot_index_magazine = Some(pocket);
+    app.move_selections.transfer_target = address;
+    app.move_selections.transfer_requested = false;
+    app.app_states.move_state = Some(MoveStates::TransferSlot);
+}
+
+pub fn transfer_slot_window(app: &mut ManagingApp, ctx: &egui::Context) {
+    let (Some(pocket), Some(machine)) = (
+        app.move_selections.selected_slot_index_magazine,
+        app.selections.machine,
+    ) else {
+        return;
+    };
+    let Some(from) = current_address(app, pocket) else {
+        return;
+    };
+    let mut is_window_open = true;
+    let selections = &mut app.move_selections;
+    let target = &mut selections.transfer_target;
+    egui::Window::new(format!("Move slot {}", pocket))
+        .open(&mut is_window_open)
+        .show(ctx, |ui| {
+            let source = &app.machines[machine].magazines[from.magazine].contents[pocket];
+            ui.label(
+                source
+                    .contents_summary()
+                    .unwrap_or(format!("Slot {}: empty", pocket)),
+            );
+            ui.separator();
+            egui::ComboBox::from_label("Machine")
+                .selected_text(app.machines[target.machine].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, machine) in app.machines.iter().enumerate() {
+                        if ui
+                            .selectable_label(target.machine == i, &machine.name)
+                            .clicked()
+                        {
+                            target.machine = i;
+                            target.magazine = 0;
+                        }
+                    }
+                });
+            let magazines = &app.machines[target.machine].magazines;
+            egui::ComboBox::from_label("Magazine")
+                .selected_text(magazines[target.magazine].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, magazine) in magazines.iter().enumerate() {
+                        ui.selectable_value(&mut target.magazine, i, &magazine.name);
+                    }
+                });
+            let contents = &magazines[target.magazine].contents;
+            target.pocket = target.pocket.min(contents.len() - 1);
+            ui.horizontal(|ui| {
+                ui.label("Slot:");
+                ui.add(
+                    egui::DragValue::new(&mut target.pocket).clamp_range(0..=contents.len() - 1),
+                );
+            });
+            let occupant = contents[target.pocket].contents_summary();
+            ui.label(
+                occupant
+                    .clone()
+                    .unwrap_or("Target slot is empty".to_string()),
+            );
+            ui.separator();
+            let action = if occupant.is_some() { "Swap" } else { "Move" };
+            if ui
+                .add_enabled(*target != from, egui::Button::new(action))
+                .clicked()
+            {
+                selections.transfer_requested = true;
+            }
+        });
+    if !is_window_open {
+        reset_states(app);
+        return;
+    }
+    if app.move_selections.transfer_requested {
+        let to = app.move_selections.transfer_target;
+        transfer_slot(app, from, to);
+    }
+}
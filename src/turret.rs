@@ -0,0 +1,111 @@
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::compatibility::*;
+
+/// How a magazine holds its tools.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum MagazineKind {
+    #[default]
+    Chain, // Chain or carousel magazine feeding a spindle
+    Turret, // Lathe turret, every slot is a station
+}
+
+impl fmt::Display for MagazineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MagazineKind::Chain => write!(f, "Chain magazine"),
+            MagazineKind::Turret => write!(f, "Turret"),
+        }
+    }
+}
+
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum StationOrientation {
+    Axial, // Tool points along Z
+    #[default]
+    Radial, // Tool points along X
+}
+
+impl fmt::Display for StationOrientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StationOrientation::Axial => write!(f, "Axial"),
+            StationOrientation::Radial => write!(f, "Radial"),
+        }
+    }
+}
+
+/// Side of the spindle centre line the tool cuts on.
+#[derive(
+    serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter,
+)]
+pub enum CentreSide {
+    Above,
+    #[default]
+    Below,
+}
+
+impl fmt::Display for CentreSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CentreSide::Above => write!(f, "Above centre"),
+            CentreSide::Below => write!(f, "Below centre"),
+        }
+    }
+}
+
+/// A station of a lathe turret.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TurretStation {
+    pub driven: bool, // Live tooling
+    pub orientation: StationOrientation,
+    pub centre_side: CentreSide,
+    pub interface: Option<ToolInterface>, // VDI or BMT size of the station
+}
+
+impl TurretStation {
+    /// Orientation of a lathe tool mounted in the station.
+    pub fn orientation_text(&self) -> String {
+        format!("{}, {}", self.orientation, self.centre_side)
+    }
+
+    pub fn display(&self, ui: &mut egui::Ui) {
+        ui.separator();
+        let drive = if self.driven { "Live" } else { "Static" };
+        ui.label(drive).on_hover_text(format!(
+            "{} {} station",
+            drive,
+            self.interface
+                .map(|interface| interface.to_string())
+                .unwrap_or_default()
+        ));
+        ui.label(self.orientation_text());
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Station:");
+            ui.radio_value(&mut self.driven, false, "Static");
+            ui.radio_value(&mut self.driven, true, "Live");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Orientation:");
+            for orientation in StationOrientation::iter() {
+                ui.radio_value(&mut self.orientation, orientation, orientation.to_string());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Cuts:");
+            for side in CentreSide::iter() {
+                ui.radio_value(&mut self.centre_side, side, side.to_string());
+            }
+        });
+        interface_combo(ui, "Station interface", &mut self.interface);
+    }
+}